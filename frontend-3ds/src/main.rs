@@ -1,7 +1,49 @@
 #![feature(allocator_api)]
 
-use citro3d::{attrib::{Format, Info, Register}, buffer::Primitive, math::{AspectRatio, ClipPlanes, Matrix4, Projection}, shader::{Library, Program}, Instance};
+use citro3d::{attrib::{Format, Info, Register}, buffer::Primitive, math::{ClipPlanes, Matrix4, Projection}, render::ClearFlags, shader::{Library, Program}, Instance};
 use ctru::{linear::LinearAllocator, prelude::*, services::gfx::TopScreen3D};
+use gameplay::{grid::GridPoint, event::GameEvent, input::{Direction, LineController, LineInput}, pack::PuzzlePack, session::{PuzzleSession, SessionState}};
+use render::{layout::{Layout, BOTTOM_SCREEN, TOP_SCREEN}, mesh::{build_puzzle_mesh, MeshVertex}, renderer::{Overlay, Renderer}, style::{Rgba, Style}};
+use std::collections::HashSet;
+
+/// How far from a start a tap can be to start a line there, in grid units
+const START_TAP_RADIUS: f32 = 0.5;
+/// How far the circle pad reads when it's pushed all the way
+const CIRCLE_PAD_RANGE: f32 = 156.;
+/// The pack that's played, from the romfs
+const PACK_PATH: &str = "romfs:/packs/tutorial.pack";
+
+/// Converts a position on the touch screen to a position in grid space, as if the puzzle was laid out on it
+fn touch_to_grid(layout: &Layout, (x, y): (u16, u16)) -> GridPoint {
+    layout.screen_to_grid(GridPoint::new(x as f32, y as f32))
+}
+
+/// Maps the d-pad, A and B to moves of the line, the same way the SDL2 frontend maps a game controller
+fn get_key_input(keys: KeyPad) -> Vec<LineInput> {
+    [
+        (KeyPad::DPAD_UP, LineInput::Step(Direction::Up)),
+        (KeyPad::DPAD_DOWN, LineInput::Step(Direction::Down)),
+        (KeyPad::DPAD_LEFT, LineInput::Step(Direction::Left)),
+        (KeyPad::DPAD_RIGHT, LineInput::Step(Direction::Right)),
+        (KeyPad::A, LineInput::Confirm),
+        (KeyPad::B, LineInput::Cancel),
+    ]
+    .into_iter()
+    .filter(|&(key, _)| keys.contains(key))
+    .map(|(_, input)| input)
+    .collect()
+}
+
+/// Reads how far the circle pad is pushed, from -1 to 1 with y pointing down like in grid space
+fn get_circle_pad_stick(hid: &Hid) -> GridPoint {
+    let (x, y) = hid.circlepad_position();
+    GridPoint::new(x as f32, -(y as f32)) / CIRCLE_PAD_RANGE
+}
+
+/// Packs a color the way render targets are cleared with, as 0xRRGGBBAA
+fn to_clear_color(color: Rgba) -> u32 {
+    u32::from_be_bytes([color.r, color.g, color.b, color.a])
+}
 
 fn main() {
     let apt = Apt::new().unwrap();
@@ -14,76 +56,42 @@ fn main() {
     let (mut left_eye, mut _right_eye) = top_screen.split_mut();
 
     // Create a render target
-    let top_screen_render_target = gpu.render_target(400, 240, left_eye, None).unwrap();
+    let mut top_screen_render_target = gpu.render_target(400, 240, left_eye, None).unwrap();
 
     // Load shader program
     let vshader = std::fs::read("romfs:/shaders/projection_vcolor.v.pica.bin").unwrap();
     let shader_library = Library::from_bytes(&vshader).unwrap();
     let entrypoint = shader_library.get(0).unwrap();
     let shader_program = Program::new(entrypoint).unwrap();
-
-    gpu.render_frame_with(|gpu| {
-        // Select target
-        println!("Trace 1: Select target");
-        gpu.select_render_target(&top_screen_render_target).unwrap();
-
-        // Bind shader program
-        println!("Trace 2: Bind shader program");
-        gpu.bind_program(&shader_program);
-
-        // Bind uniform: projection matrix
-        println!("Trace 3: Bind uniform");
-        let projection_uniform_index = shader_program.get_uniform("projection").unwrap();
-        println!("Trace 4: Bind uniform");
-        let projection = Projection::perspective(
-            1.22173, // 70° as radians
-            AspectRatio::TopScreen,
-            ClipPlanes {
-                near: 0.01,
-                far: 100.
-            }
-        );
-        println!("Trace 5: Bind uniform");
-        let projection_matrix: Matrix4 = projection.into();
-        println!("Trace 6: Bind uniform");
-        gpu.bind_vertex_uniform(projection_uniform_index, projection_matrix);
-
-        // Bind attribute info: 3xFloat Position, 3xFloat Color
-        println!("Trace 7: Bind attribute info");
-        let mut attr_info = Info::new();
-        println!("Trace 8: Bind attribute info");
-        attr_info.add_loader(Register::new(0).unwrap(), Format::Float, 3).unwrap(); // Position
-        println!("Trace 9: Bind attribute info");
-        attr_info.add_loader(Register::new(1).unwrap(), Format::Float, 3).unwrap(); // Color
-        println!("Trace A: Bind attribute info");
-        gpu.set_attr_info(&attr_info);
-
-        // Create vertex buffer object and draw it
-        println!("Trace B: Create vertex buffer object and draw it");
-        let mut vbinfo = citro3d::buffer::Info::new();
-        println!("Trace C: Create vertex buffer object and draw it");
-        let vbo: Box<[f32; 18], LinearAllocator> = Box::new_in([
-            0., 1., 0.,
-            1., 0., 0.,
-
-            -1., -1., 0.,
-            0., 1., 0.,
-
-            1., -1., 0.,
-            0., 0., 1.,
-        ], LinearAllocator);
-        println!("Trace D: Create vertex buffer object and draw it");
-        let vbo_slice = vbinfo.add(vbo.as_slice(), &attr_info).unwrap();
-
-        println!("Trace E: Create vertex buffer object and draw it");
-        gpu.draw_arrays(Primitive::Triangles, vbo_slice);
-        println!("Trace F: End");
-    });
+    let projection_uniform_index = shader_program.get_uniform("projection").unwrap();
+    // Meshes are laid out in pixels, so the projection maps pixels on the top screen to the GPU's coordinates
+    let projection: Matrix4 = Projection::orthographic(
+        0.0..400.0,
+        240.0..0.0,
+        ClipPlanes { near: -1., far: 1. },
+    ).into();
+
+    // Vertex attributes: 3xFloat Position, 4xFloat Color, laid out like a MeshVertex
+    let mut attr_info = Info::new();
+    attr_info.add_loader(Register::new(0).unwrap(), Format::Float, 3).unwrap(); // Position
+    attr_info.add_loader(Register::new(1).unwrap(), Format::Float, 4).unwrap(); // Color
+    let style = Style::default();
 
     println!("Hello, World!");
     println!("Call to gameplay crate: {}", gameplay::add(1, 2));
     println!("\x1b[29;11HPress Start to exit");
 
+    let pack = PuzzlePack::load(PACK_PATH).unwrap_or_else(|error| panic!("Couldn't load {PACK_PATH}: {error}"));
+    println!("{}: {}", pack.title, pack.description);
+    let mut current_puzzle = 0;
+    let mut solved_puzzles: HashSet<String> = HashSet::new();
+    println!("Puzzle: {}", pack.puzzles[current_puzzle].name);
+    let mut session = PuzzleSession::new(pack.puzzles[current_puzzle].grid.clone());
+    let mut last_touch = (0, 0);
+    // The line can also be drawn with the d-pad and circle pad, starting at the start picked with them
+    let mut line_controller = LineController::new();
+    let mut show_selected_start = false;
+
     println!("Trace 10: AppLoop");
     while apt.main_loop() {
         println!("Trace 11: VBlank");
@@ -97,6 +105,88 @@ fn main() {
             break;
         }
         println!("Trace 13b: Check done");
+
+        session.update(1. / 60.);
+
+        // Move on to the next unsolved puzzle with A, once this one is solved
+        let solved = session.get_state() == SessionState::Solved;
+        if hid.keys_down().contains(KeyPad::A) && solved {
+            match pack.get_next_unsolved(current_puzzle + 1, &solved_puzzles) {
+                Some(next) => {
+                    current_puzzle = next;
+                    println!("Puzzle: {}", pack.puzzles[current_puzzle].name);
+                    session = PuzzleSession::new(pack.puzzles[current_puzzle].grid.clone());
+                }
+                None => println!("Every puzzle in the pack is solved!"),
+            }
+        }
+
+        // The d-pad steps the line from vertex to vertex and the circle pad moves it continuously, A starts and submits it and B cancels it
+        for input in get_key_input(hid.keys_down()) {
+            if !(solved && input == LineInput::Confirm) {
+                line_controller.handle_input(&mut session, input);
+                show_selected_start = true;
+            }
+        }
+        line_controller.set_stick(get_circle_pad_stick(&hid));
+        line_controller.update(&mut session, 1. / 60.);
+
+        // The puzzle is drawn on the top screen, and the touch screen maps onto it as if it was drawn there too
+        let top_layout = Layout::fit(session.get_grid(), &style, TOP_SCREEN);
+        let touch_layout = Layout::fit(session.get_grid(), &style, BOTTOM_SCREEN);
+
+        // Tapping on a start starts a line, dragging moves it, and lifting the stylus lets go of it
+        let touch = hid.touch_position();
+        if hid.keys_down().contains(KeyPad::TOUCH) {
+            show_selected_start = false;
+            session.start_near(touch_to_grid(&touch_layout, touch), START_TAP_RADIUS);
+        } else if hid.keys_held().contains(KeyPad::TOUCH) {
+            session.move_by(touch_to_grid(&touch_layout, touch) - touch_to_grid(&touch_layout, last_touch));
+        } else if hid.keys_up().contains(KeyPad::TOUCH) {
+            session.release();
+        }
+        // The touch position reads as zero once the stylus is lifted, so only remember it while it's down
+        if hid.keys_held().contains(KeyPad::TOUCH) {
+            last_touch = touch;
+        }
+        for event in session.drain_events() {
+            if let GameEvent::Solved = event {
+                solved_puzzles.insert(pack.puzzles[current_puzzle].id.clone());
+                if pack.get_next_unsolved(current_puzzle + 1, &solved_puzzles).is_some() {
+                    println!("Solved! Press A for the next puzzle");
+                }
+            }
+        }
+
+        // Draw the puzzle on the top screen
+        let top_style = top_layout.adjust_style(&style);
+        let mut mesh = build_puzzle_mesh(session.get_grid(), &Overlay::for_session(&session, &top_style), &top_style);
+        // Show which start the d-pad and A would start the line at
+        if show_selected_start && session.get_state() != SessionState::Drawing {
+            if let Some(start) = line_controller.get_selected_start_point(&session) {
+                mesh.fill_circle(start, top_style.start_radius * 1.5, top_style.line.with_alpha(0x60));
+            }
+        }
+        mesh.transform(top_layout.get_scale(), top_layout.get_offset());
+        // The GPU reads vertices straight from linear memory, which has to outlive the frame
+        let mut vbo: Vec<MeshVertex, LinearAllocator> = Vec::with_capacity_in(mesh.vertices.len(), LinearAllocator);
+        vbo.extend(mesh.vertices);
+        gpu.render_frame_with(|gpu| {
+            top_screen_render_target.clear(ClearFlags::ALL, to_clear_color(style.background), 0);
+            gpu.select_render_target(&top_screen_render_target).unwrap();
+            gpu.bind_program(&shader_program);
+            gpu.bind_vertex_uniform(projection_uniform_index, projection);
+            gpu.set_attr_info(&attr_info);
+            // Meshes are flat, drawn back to front at the same depth, and their triangles wind both ways.
+            // citro3d starts out testing depth and culling back faces, which would throw most of them away.
+            unsafe {
+                citro3d_sys::C3D_DepthTest(false, ctru_sys::GPU_ALWAYS, ctru_sys::GPU_WRITE_COLOR);
+                citro3d_sys::C3D_CullFace(ctru_sys::GPU_CULL_NONE);
+            }
+            let mut vbinfo = citro3d::buffer::Info::new();
+            let vbo_slice = vbinfo.add(vbo.as_slice(), &attr_info).unwrap();
+            gpu.draw_arrays(Primitive::Triangles, vbo_slice);
+        });
     }
 
     println!("Trace 14: Drop");
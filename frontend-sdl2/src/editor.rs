@@ -0,0 +1,238 @@
+use gameplay::editor::{EditorTarget, PuzzleEditor, SolutionCount, VERTEX_PICK_RADIUS};
+use gameplay::grid::{Color, Grid, GridPoint};
+use render::layout::Layout;
+use render::renderer::{get_bounds, Renderer};
+use render::style::{get_symbol_color, Style};
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
+use witness_core::*;
+
+/// The colors squares and stars can be given, in the order of the number keys that pick them
+const PALETTE: [Color; 9] = [
+    Color::Black,
+    Color::White,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Orange,
+];
+/// Where puzzles are saved when they weren't loaded from a file
+const DEFAULT_SAVE_PATH: &str = "edited-puzzle.txt";
+
+/// Gets the palette's swatches as their colors and centers, lined up in the top right corner of the background
+fn get_swatches(grid: &Grid, style: &Style) -> impl Iterator<Item = (Color, GridPoint)> {
+    let (origin, bounds) = get_bounds(grid, style);
+    let spacing = style.background_margin;
+    PALETTE
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(move |(i, color)| {
+            (
+                color,
+                vec2!(
+                    origin.x + bounds.x - spacing * (i as f32 + 0.5),
+                    origin.y + spacing / 2.
+                ),
+            )
+        })
+}
+
+/// How big a swatch is around its center
+fn get_swatch_radius(style: &Style) -> f32 {
+    style.background_margin * 0.4
+}
+
+/// Tells the editor how many solutions the puzzle has
+fn get_solution_text(count: SolutionCount) -> String {
+    match count {
+        SolutionCount::Exact(0) => "No solutions".to_string(),
+        SolutionCount::Exact(1) => "1 solution".to_string(),
+        SolutionCount::Exact(count) => format!("{count} solutions"),
+        // The solver gave up before finding any, which happens on big puzzles with few solutions
+        SolutionCount::AtLeast(0) => "Too many paths to count solutions".to_string(),
+        SolutionCount::AtLeast(count) => format!("{count}+ solutions"),
+    }
+}
+
+/// Editing a puzzle with the mouse and keyboard
+pub struct EditorScreen {
+    pub editor: PuzzleEditor,
+    /// What's under the mouse, which is what a click changes
+    hovered: Option<EditorTarget>,
+    save_path: String,
+    /// How the last save went, with the error if it failed, shown until something else is done
+    last_save: Option<Result<(), String>>,
+}
+
+impl EditorScreen {
+    pub fn new(grid: Grid, save_path: Option<&str>) -> Self {
+        EditorScreen {
+            editor: PuzzleEditor::new(grid),
+            hovered: None,
+            save_path: save_path.unwrap_or(DEFAULT_SAVE_PATH).to_string(),
+            last_save: None,
+        }
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        self.editor.get_grid()
+    }
+
+    /// Saves the puzzle in the text format, over the file it was loaded from if there is one
+    fn save(&mut self) {
+        self.last_save = Some(
+            std::fs::write(&self.save_path, self.get_grid().to_text()).map_err(|error| {
+                format!("Couldn't save the puzzle to {}: {error}", self.save_path)
+            }),
+        );
+    }
+
+    /// Whether the last save failed, which the status shows as an error
+    pub fn has_save_error(&self) -> bool {
+        matches!(self.last_save, Some(Err(_)))
+    }
+
+    pub fn handle_event(&mut self, event: &Event, layout: &Layout, style: &Style) {
+        // How the last save went stays in the status until the next click or key press
+        if matches!(event, Event::MouseButtonDown { .. } | Event::KeyDown { .. }) {
+            self.last_save = None;
+        }
+        match *event {
+            // Whatever the mouse is over gets highlighted
+            Event::MouseMotion { x, y, .. } => {
+                self.hovered = self
+                    .editor
+                    .get_target_at(layout.screen_to_grid(vec2!(x as f32, y as f32)));
+            }
+            // Clicking on a swatch picks its color, clicking on the puzzle changes what's there, right clicking goes backwards
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => {
+                let point = layout.screen_to_grid(vec2!(x as f32, y as f32));
+                let swatch = get_swatches(self.get_grid(), style)
+                    .find(|&(_, center)| (point - center).mag() <= get_swatch_radius(style));
+                if let Some((color, _)) = swatch {
+                    self.editor.set_color(color);
+                } else if let Some(target) = self.editor.get_target_at(point) {
+                    match mouse_btn {
+                        MouseButton::Left => self.editor.cycle(target, true),
+                        MouseButton::Right => self.editor.cycle(target, false),
+                        _ => {}
+                    }
+                }
+            }
+            // Save with Ctrl+S
+            Event::KeyDown {
+                keycode: Some(Keycode::S),
+                keymod,
+                repeat: false,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => self.save(),
+            // Undo and redo changes with Ctrl+Z and Ctrl+Y
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::Z | Keycode::Y)),
+                keymod,
+                ..
+            } if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) => {
+                if keycode == Keycode::Z {
+                    self.editor.undo();
+                } else {
+                    self.editor.redo();
+                }
+                // Undoing a resize moves what was under the mouse
+                self.hovered = None;
+            }
+            // The arrow keys make the grid wider, narrower, taller and shorter
+            Event::KeyDown {
+                keycode:
+                    Some(keycode @ (Keycode::Left | Keycode::Right | Keycode::Up | Keycode::Down)),
+                ..
+            } => {
+                let size = self.get_grid().get_size();
+                self.editor.resize(match keycode {
+                    Keycode::Left => vec2!(size.x.saturating_sub(1), size.y),
+                    Keycode::Right => vec2!(size.x + 1, size.y),
+                    Keycode::Up => vec2!(size.x, size.y.saturating_sub(1)),
+                    _ => vec2!(size.x, size.y + 1),
+                });
+                // What was under the mouse has moved
+                self.hovered = None;
+            }
+            // The number keys pick colors
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => {
+                let number_keys = [
+                    Keycode::Num1,
+                    Keycode::Num2,
+                    Keycode::Num3,
+                    Keycode::Num4,
+                    Keycode::Num5,
+                    Keycode::Num6,
+                    Keycode::Num7,
+                    Keycode::Num8,
+                    Keycode::Num9,
+                ];
+                if let Some(index) = number_keys.iter().position(|&key| key == keycode) {
+                    self.editor.set_color(PALETTE[index]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Draws the highlight over what's under the mouse and the palette, on top of the puzzle
+    pub fn draw(&self, renderer: &mut impl Renderer, style: &Style) {
+        let grid = self.get_grid();
+        let highlight = style.line.with_alpha(0x60);
+        let position = |vertex| vec2cast!(grid.get_vertex_position(vertex).unwrap(), f32);
+        match self.hovered {
+            Some(EditorTarget::Vertex(vertex)) => {
+                renderer.fill_circle(position(vertex), VERTEX_PICK_RADIUS, highlight)
+            }
+            Some(EditorTarget::Edge(edge)) => {
+                let (a, b) = grid.snapshot().get_edge(edge).unwrap().get_vertices_tuple();
+                renderer.draw_line(
+                    &[position(a), position(b)],
+                    style.line_width * 1.5,
+                    highlight,
+                );
+            }
+            Some(EditorTarget::Cell(cell)) => {
+                let corner = vec2cast!(grid.get_cell_position(cell).unwrap(), f32);
+                let outline = [vec2!(0., 0.), vec2!(1., 0.), vec2!(1., 1.), vec2!(0., 1.)]
+                    .map(|offset| corner + offset);
+                renderer.fill_polygon(&outline, highlight);
+            }
+            None => {}
+        }
+
+        // The picked color is ringed
+        let radius = get_swatch_radius(style);
+        for (color, center) in get_swatches(grid, style) {
+            if color == self.editor.get_color() {
+                renderer.fill_circle(center, radius * 1.25, style.line);
+            }
+            renderer.fill_circle(center, radius, get_symbol_color(color));
+        }
+    }
+
+    /// Tells the editor how the last save went, or how many solutions the puzzle has and what they can do
+    pub fn get_status_text(&self) -> String {
+        match &self.last_save {
+            Some(Ok(())) => return format!("Saved the puzzle to {}", self.save_path),
+            Some(Err(error)) => return error.clone(),
+            None => {}
+        }
+        format!(
+            "{} - Click to edit, arrows resize, 1-9 colors, Ctrl+Z/Y undo/redo, Ctrl+S save, E play",
+            get_solution_text(self.editor.get_solution_count())
+        )
+    }
+}
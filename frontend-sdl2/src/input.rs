@@ -0,0 +1,40 @@
+use gameplay::grid::GridPoint;
+use gameplay::input::{Direction, LineInput};
+use sdl2::controller::{Axis, Button};
+use sdl2::keyboard::Keycode;
+
+/// Maps the arrow keys, WASD, Space and Backspace to moves of the line
+pub fn get_key_input(keycode: Keycode) -> Option<LineInput> {
+    Some(match keycode {
+        Keycode::Up | Keycode::W => LineInput::Step(Direction::Up),
+        Keycode::Down | Keycode::S => LineInput::Step(Direction::Down),
+        Keycode::Left | Keycode::A => LineInput::Step(Direction::Left),
+        Keycode::Right | Keycode::D => LineInput::Step(Direction::Right),
+        Keycode::Space => LineInput::Confirm,
+        Keycode::Backspace => LineInput::Cancel,
+        _ => return None,
+    })
+}
+
+/// Maps a game controller's d-pad, A and B to moves of the line
+pub fn get_button_input(button: Button) -> Option<LineInput> {
+    Some(match button {
+        Button::DPadUp => LineInput::Step(Direction::Up),
+        Button::DPadDown => LineInput::Step(Direction::Down),
+        Button::DPadLeft => LineInput::Step(Direction::Left),
+        Button::DPadRight => LineInput::Step(Direction::Right),
+        Button::A => LineInput::Confirm,
+        Button::B => LineInput::Cancel,
+        _ => return None,
+    })
+}
+
+/// Updates how far the left stick is pushed when one of its axes moves, from -1 to 1 with y pointing down like in grid space
+pub fn update_stick(stick: &mut GridPoint, axis: Axis, value: i16) {
+    let value = (value as f32 / i16::MAX as f32).max(-1.);
+    match axis {
+        Axis::LeftX => stick.x = value,
+        Axis::LeftY => stick.y = value,
+        _ => {}
+    }
+}
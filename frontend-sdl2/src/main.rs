@@ -1,41 +1,125 @@
 extern crate sdl2;
-#[macro_use]
-extern crate witness_core;
 
-use gameplay::grid::{Grid, VertexID};
+mod editor;
+mod input;
+mod renderer;
+mod source;
+mod watcher;
+
+use editor::EditorScreen;
+use gameplay::analysis::{EdgeAnalysis, EdgeUsage};
+use gameplay::event::GameEvent;
+use gameplay::grid::GridPoint;
+use gameplay::input::LineController;
+use gameplay::session::{PuzzleSession, SessionState};
+use render::layout::{Layout, ScreenRect};
+use render::renderer::{draw_puzzle, get_bounds, Overlay, Renderer};
+use render::style::Style;
+use renderer::SdlRenderer;
+use sdl2::controller::Button;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
 use sdl2::pixels::Color;
-use sdl2::rect::Point;
-use sdl2::gfx::primitives::DrawRenderer;
+use source::PuzzleSource;
+use std::collections::HashSet;
 use std::time::Duration;
+use watcher::FileWatcher;
 use witness_core::*;
 
 const FRAME_RATE: Duration = Duration::new(0, 1_000_000_000u32 / 60);
+/// How far from a start a click can be to start a line there, in grid units
+const START_CLICK_RADIUS: f32 = 0.5;
+/// How tall text is drawn, in grid units
+const TEXT_SIZE: f32 = 0.08;
+/// The most solutions the edge overlay is worked out from, as the analysis keeps every one of them
+const ANALYSIS_SOLUTION_LIMIT: usize = 10_000;
+/// The most nodes the solver visits working out the edge overlay, so big puzzles don't freeze the game
+const ANALYSIS_NODE_BUDGET: usize = 2_000_000;
+/// How long controllers rumble for when a line fails, in milliseconds
+const FAILED_RUMBLE_DURATION: u32 = 250;
+
+/// Tells the player what they can do next
+fn get_status_text(state: SessionState) -> &'static str {
+    match state {
+        SessionState::Idle => {
+            "Click the start circle or press Space to begin, or press E to edit the puzzle"
+        }
+        SessionState::Drawing => {
+            "Click or press Space at an exit to submit, right click or Backspace to cancel"
+        }
+        SessionState::Submitted => "",
+        SessionState::Solved => "Solved!",
+        SessionState::Failed => "Not quite...",
+    }
+}
 
 pub fn main() {
-    let grid = Grid::new(vec2!(2, 4));
+    let mut source = PuzzleSource::from_args(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(2);
+    });
+    if let PuzzleSource::Pack { pack, .. } = &source {
+        println!("{} by {}: {}", pack.title, pack.author, pack.description);
+    }
+    let mut current_puzzle = 0;
+    let mut solved_puzzles: HashSet<String> = HashSet::new();
+    let mut puzzle = source.get_puzzle(current_puzzle).unwrap();
+    // The edge overlay is only worked out once it's shown, and is left out if the puzzle has too many solutions
+    let mut edge_analysis: Option<Option<EdgeAnalysis>> = None;
+    let mut show_edge_analysis = false;
+    let mut session = PuzzleSession::new(puzzle.grid.clone());
+    // While editing, the puzzle is changed here instead of being played
+    let mut editor: Option<EditorScreen> = None;
+    // The puzzles are loaded again whenever their file changes, and if that fails the last good ones are kept
+    let mut watcher = source.get_path().map(FileWatcher::new);
+    let mut reload_error: Option<String> = None;
+    // The line can also be drawn with the keyboard or a game controller, starting at the start picked with them
+    let mut line_controller = LineController::new();
+    let mut stick = GridPoint::new(0., 0.);
+    let mut show_selected_start = false;
 
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
 
     let window = video_subsystem
-        .window("rust-sdl2 demo", 800, 600)
+        .window("The Witness", 800, 600)
         .position_centered()
+        .resizable()
         .build()
         .unwrap();
 
     let mut canvas = window.into_canvas().build().unwrap();
+    let mouse = sdl_context.mouse();
+    // Game controllers are opened as they're plugged in, which also happens for those plugged in at launch
+    let controller_subsystem = sdl_context.game_controller().unwrap();
+    let mut controllers = vec![];
 
+    let style = Style::default();
     let mut event_pump = sdl_context.event_pump().unwrap();
+    let mut last_frame_time = std::time::Instant::now();
     'running: loop {
         let frame_start_time = std::time::Instant::now();
+        let delta_time = frame_start_time
+            .duration_since(last_frame_time)
+            .as_secs_f32();
+        session.update(delta_time);
+        if editor.is_none() {
+            line_controller.set_stick(stick);
+            line_controller.update(&mut session, delta_time);
+        }
+        last_frame_time = frame_start_time;
 
-        // Clear the screen and make it black
-        canvas.set_draw_color(Color::BLACK);
-        canvas.clear();
+        // Fit the puzzle to the window every frame, so it follows the window when it's resized
+        let (width, height) = canvas.output_size().unwrap();
+        let grid = editor
+            .as_ref()
+            .map_or(session.get_grid(), EditorScreen::get_grid);
+        let layout = Layout::fit(grid, &style, ScreenRect::from_size(width, height));
 
         // Handle events
+        let mut next_puzzle = None;
+        let mut toggle_editor = false;
         for event in event_pump.poll_iter() {
             match event {
                 // Quit when escape is pressed or the application is closed
@@ -44,49 +128,301 @@ pub fn main() {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => break 'running,
+                // Switch between editing the puzzle and playing it with E
+                Event::KeyDown {
+                    keycode: Some(Keycode::E),
+                    repeat: false,
+                    ..
+                } => toggle_editor = true,
+                // Open game controllers when they're plugged in, and close them when they're unplugged
+                Event::ControllerDeviceAdded { which, .. } => {
+                    match controller_subsystem.open(which) {
+                        Ok(controller) => {
+                            println!("Controller connected: {}", controller.name());
+                            controllers.push(controller);
+                        }
+                        Err(error) => eprintln!("Couldn't open controller {which}: {error}"),
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.retain(|controller| controller.instance_id() != which);
+                    stick = GridPoint::new(0., 0.);
+                }
+                // A controller's stick moves the line continuously, and is kept track of even while editing so it doesn't get stuck
+                Event::ControllerAxisMotion { axis, value, .. } => {
+                    input::update_stick(&mut stick, axis, value)
+                }
+                // While editing, the editor gets every other event
+                event if editor.is_some() => editor
+                    .as_mut()
+                    .unwrap()
+                    .handle_event(&event, &layout, &style),
+                // Toggle the forced/forbidden edge overlay when Tab is pressed
+                Event::KeyDown {
+                    keycode: Some(Keycode::Tab),
+                    repeat: false,
+                    ..
+                } => show_edge_analysis = !show_edge_analysis,
+                // Undo and redo steps of the line being drawn with Z and Y
+                Event::KeyDown {
+                    keycode: Some(Keycode::Z),
+                    ..
+                } => _ = session.undo_line_step(),
+                Event::KeyDown {
+                    keycode: Some(Keycode::Y),
+                    ..
+                } => _ = session.redo_line_step(),
+                // Move on to the next unsolved puzzle with Enter or a controller's Start button, once this one is solved
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    repeat: false,
+                    ..
+                }
+                | Event::ControllerButtonDown {
+                    button: Button::Start,
+                    ..
+                } if session.get_state() == SessionState::Solved => {
+                    next_puzzle = match &source {
+                        PuzzleSource::Pack { pack, .. } => {
+                            pack.get_next_unsolved(current_puzzle + 1, &solved_puzzles)
+                        }
+                        _ => Some(current_puzzle + 1),
+                    };
+                    if next_puzzle.is_none() {
+                        println!("Every puzzle in the pack is solved!");
+                    }
+                }
+                // Go through the puzzles freely with N and P
+                Event::KeyDown {
+                    keycode: Some(Keycode::N),
+                    ..
+                } => next_puzzle = Some(current_puzzle + 1),
+                Event::KeyDown {
+                    keycode: Some(Keycode::P),
+                    ..
+                } => next_puzzle = current_puzzle.checked_sub(1),
+                // The arrow keys, WASD and a controller's d-pad step the line from vertex to vertex
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if input::get_key_input(keycode).is_some() => {
+                    line_controller
+                        .handle_input(&mut session, input::get_key_input(keycode).unwrap());
+                    show_selected_start = true;
+                }
+                Event::ControllerButtonDown { button, .. }
+                    if input::get_button_input(button).is_some() =>
+                {
+                    line_controller
+                        .handle_input(&mut session, input::get_button_input(button).unwrap());
+                    show_selected_start = true;
+                }
+                // Clicking on a start starts a line, and clicking again lets go of it, submitting it if it's at an exit
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    show_selected_start = false;
+                    if session.get_state() == SessionState::Drawing {
+                        session.release();
+                    } else {
+                        session.start_near(
+                            layout.screen_to_grid(vec2!(x as f32, y as f32)),
+                            START_CLICK_RADIUS,
+                        );
+                    }
+                    // Lock the cursor while drawing so the line can follow it past the edge of the window
+                    let drawing = session.get_state() == SessionState::Drawing;
+                    mouse.set_relative_mouse_mode(drawing);
+                    if !drawing {
+                        // Bring the cursor back where the line ended, rather than where it was locked
+                        if let Some(&head) = session.get_line().last() {
+                            let head = layout.grid_to_screen(head);
+                            mouse.warp_mouse_in_window(
+                                canvas.window(),
+                                head.x as i32,
+                                head.y as i32,
+                            );
+                        }
+                    }
+                }
+                // Right clicking throws the line away
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Right,
+                    ..
+                } => {
+                    session.abandon();
+                    mouse.set_relative_mouse_mode(false);
+                }
+                // The line follows the mouse
+                Event::MouseMotion { xrel, yrel, .. } => {
+                    session.move_by(layout.screen_delta_to_grid(vec2!(xrel as f32, yrel as f32)));
+                }
                 // For all other events, do nothing
                 _ => {}
             }
         }
 
-        // - Draw grid -
-
-        let vertex_to_drawable_point =
-            |v: VertexID| vec2cast!(grid.get_vertex_position(v).unwrap() * 3 + 1, i32).into_tuple();
-
-        // Draw cells
-        grid.get_all_cellrefs_iter().for_each(|cell| {
-            let (xvec, yvec) = cell.read().unwrap().get_vertices_in_winding_order().into_iter().fold((vec![], vec![]), |mut acc: (Vec<i16>, Vec<i16>), vertex| {
-                let point = vertex_to_drawable_point(vertex);
-                acc.0.push(point.0 as i16);
-                acc.1.push(point.1 as i16);
-                acc
-            });
-            canvas.filled_polygon(&xvec, &yvec, Color::RED).unwrap();
-        });
-
-        // Draw edges
-        canvas.set_draw_color(Color::BLUE);
-        grid.get_all_edgerefs_iter().for_each(|edge| {
-            let vertices = edge.read().unwrap().get_vertices_tuple();
-            canvas
-                .draw_line(
-                    vertex_to_drawable_point(vertices.0),
-                    vertex_to_drawable_point(vertices.1),
-                )
-                .unwrap()
-        });
-
-        // Draw vertices
-        canvas.set_draw_color(Color::GREEN);
-        canvas
-            .draw_points(
-                grid.get_all_vertex_ids_iter()
-                    .map(|vertex| vertex_to_drawable_point(vertex).into())
-                    .collect::<Vec<Point>>()
-                    .as_slice(),
-            )
-            .unwrap();
+        // Start editing what's being played, or play what's been edited
+        if toggle_editor {
+            editor = match editor.take() {
+                Some(editor) => {
+                    session = PuzzleSession::new(editor.get_grid().clone());
+                    edge_analysis = None;
+                    None
+                }
+                None => Some(EditorScreen::new(
+                    session.get_grid().clone(),
+                    source.get_puzzle_path(),
+                )),
+            };
+            mouse.set_relative_mouse_mode(false);
+        }
+
+        // Load the puzzles again if their file changed, staying on the same puzzle if it's still there
+        if watcher.as_mut().is_some_and(FileWatcher::has_changed) {
+            match source.reload() {
+                Ok(reloaded) => {
+                    source = reloaded;
+                    reload_error = None;
+                    current_puzzle = (0..=current_puzzle)
+                        .rev()
+                        .find(|&index| source.has_puzzle(index))
+                        .unwrap_or(0);
+                    if let Some(reloaded) = source.get_puzzle(current_puzzle) {
+                        puzzle = reloaded;
+                        println!("Reloaded: {}", puzzle.name);
+                        edge_analysis = None;
+                        // Anything being edited stays as it is, the reloaded puzzle is there to play once editing is done
+                        session = PuzzleSession::new(puzzle.grid.clone());
+                        mouse.set_relative_mouse_mode(false);
+                    }
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    reload_error = Some(error);
+                }
+            }
+        }
+
+        // Switch puzzles if asked to, as long as there's a puzzle to switch to
+        if let Some(next) =
+            next_puzzle.filter(|&next| next != current_puzzle && source.has_puzzle(next))
+        {
+            if let Some(next_puzzle) = source.get_puzzle(next) {
+                current_puzzle = next;
+                puzzle = next_puzzle;
+                println!("Puzzle: {}", puzzle.name);
+                edge_analysis = None;
+                session = PuzzleSession::new(puzzle.grid.clone());
+                editor = None;
+                mouse.set_relative_mouse_mode(false);
+            }
+        }
+
+        // React to what happened during play: solved puzzles are remembered, and controllers rumble when a line fails
+        for event in session.drain_events() {
+            match event {
+                GameEvent::Solved => {
+                    if let Some(id) = &puzzle.id {
+                        solved_puzzles.insert(id.clone());
+                    }
+                }
+                GameEvent::Failed => {
+                    for controller in &mut controllers {
+                        // Controllers that can't rumble just stay still
+                        _ = controller.set_rumble(0x4000, 0xc000, FAILED_RUMBLE_DURATION);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // - Draw puzzle -
+
+        let style = layout.adjust_style(&style);
+        canvas.set_draw_color(Color::RGB(
+            style.background.r,
+            style.background.g,
+            style.background.b,
+        ));
+        canvas.clear();
+
+        let grid = editor
+            .as_ref()
+            .map_or(session.get_grid(), EditorScreen::get_grid);
+        let mut overlay = match editor {
+            Some(_) => Overlay::default(),
+            None => Overlay::for_session(&session, &style),
+        };
+        // With the overlay on, edges every solution uses are highlighted and edges no solution uses are greyed out
+        let analysis_shown = show_edge_analysis && editor.is_none();
+        let shown_analysis = if analysis_shown {
+            edge_analysis
+                .get_or_insert_with(|| {
+                    session
+                        .get_grid()
+                        .analyze_edges_within(ANALYSIS_SOLUTION_LIMIT, ANALYSIS_NODE_BUDGET)
+                })
+                .as_ref()
+        } else {
+            None
+        };
+        if let Some(analysis) = shown_analysis {
+            overlay.edge_colors = analysis
+                .get_usage_iter()
+                .filter_map(|(edge, usage)| match usage {
+                    EdgeUsage::Forced => Some((edge, style.solved_line)),
+                    EdgeUsage::Forbidden => Some((edge, style.failed_line)),
+                    _ => None,
+                })
+                .collect();
+        }
+
+        let mut renderer = SdlRenderer {
+            canvas: &mut canvas,
+            layout,
+        };
+        draw_puzzle(&mut renderer, grid, &overlay, &style);
+        if let Some(editor) = &editor {
+            editor.draw(&mut renderer, &style);
+        } else if show_selected_start && session.get_state() != SessionState::Drawing {
+            // Show which start the keyboard or controller would start the line at
+            if let Some(start) = line_controller.get_selected_start_point(&session) {
+                renderer.fill_circle(start, style.start_radius * 1.5, style.line.with_alpha(0x60));
+            }
+        }
+        // The puzzle's name goes in the top left corner, and what to do next in the bottom left corner
+        let (origin, bounds) = get_bounds(grid, &style);
+        let text_margin = style.background_margin / 2.;
+        renderer.draw_text(origin + text_margin, TEXT_SIZE, &puzzle.name, style.line);
+        // Errors from reloading the puzzles take the place of the status until the file is fixed
+        let status = match (&reload_error, &editor, session.get_state()) {
+            (Some(error), _, _) => error.clone(),
+            (None, Some(editor), _) => editor.get_status_text(),
+            (None, None, SessionState::Idle) if analysis_shown && shown_analysis.is_none() => {
+                "This puzzle has too many solutions to show which edges they use".to_string()
+            }
+            (None, None, SessionState::Solved) if source.has_puzzle(current_puzzle + 1) => {
+                "Solved! Press Enter for the next puzzle".to_string()
+            }
+            (None, None, state) => get_status_text(state).to_string(),
+        };
+        renderer.draw_text(
+            vec2!(
+                origin.x + text_margin,
+                origin.y + bounds.y - text_margin - TEXT_SIZE
+            ),
+            TEXT_SIZE,
+            &status,
+            if reload_error.is_some() || editor.as_ref().is_some_and(EditorScreen::has_save_error) {
+                style.failing
+            } else {
+                style.line
+            },
+        );
 
         canvas.present();
         std::thread::sleep(FRAME_RATE.saturating_sub(frame_start_time.elapsed()));
@@ -0,0 +1,69 @@
+use gameplay::grid::GridPoint;
+use render::layout::Layout;
+use render::renderer::Renderer;
+use render::style::Rgba;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+
+fn to_sdl_color(color: Rgba) -> Color {
+    Color::RGBA(color.r, color.g, color.b, color.a)
+}
+
+/// Draws onto an SDL canvas with SDL_gfx, placing the puzzle in the window as laid out
+pub struct SdlRenderer<'a> {
+    pub canvas: &'a mut Canvas<Window>,
+    pub layout: Layout,
+}
+
+impl SdlRenderer<'_> {
+    fn to_screen(&self, point: GridPoint) -> (i16, i16) {
+        let point = self.layout.grid_to_screen(point);
+        (point.x.round() as i16, point.y.round() as i16)
+    }
+
+    fn to_pixels(&self, length: f32) -> i16 {
+        (length * self.layout.get_scale()).round() as i16
+    }
+}
+
+impl Renderer for SdlRenderer<'_> {
+    fn fill_polygon(&mut self, outline: &[GridPoint], color: Rgba) {
+        let (xs, ys): (Vec<i16>, Vec<i16>) =
+            outline.iter().map(|&point| self.to_screen(point)).unzip();
+        self.canvas
+            .filled_polygon(&xs, &ys, to_sdl_color(color))
+            .unwrap();
+    }
+
+    fn fill_circle(&mut self, center: GridPoint, radius: f32, color: Rgba) {
+        let (x, y) = self.to_screen(center);
+        self.canvas
+            .filled_circle(x, y, self.to_pixels(radius), to_sdl_color(color))
+            .unwrap();
+    }
+
+    fn draw_line(&mut self, line: &[GridPoint], width: f32, color: Rgba) {
+        // SDL_gfx's thick lines have square ends, so circles round off the ends and corners
+        let thickness = self.to_pixels(width).clamp(1, u8::MAX as i16) as u8;
+        line.windows(2).for_each(|segment| {
+            let (from, to) = (self.to_screen(segment[0]), self.to_screen(segment[1]));
+            self.canvas
+                .thick_line(from.0, from.1, to.0, to.1, thickness, to_sdl_color(color))
+                .unwrap();
+        });
+        line.iter().for_each(|&point| {
+            let (x, y) = self.to_screen(point);
+            self.canvas
+                .filled_circle(x, y, thickness as i16 / 2, to_sdl_color(color))
+                .unwrap();
+        });
+    }
+
+    /// Text is drawn in SDL_gfx's built-in 8x8 font, whatever size is asked for
+    fn draw_text(&mut self, position: GridPoint, _size: f32, text: &str, color: Rgba) {
+        let (x, y) = self.to_screen(position);
+        self.canvas.string(x, y, text, to_sdl_color(color)).unwrap();
+    }
+}
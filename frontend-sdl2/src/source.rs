@@ -0,0 +1,230 @@
+use gameplay::generator::Generator;
+use gameplay::grid::{CellType, Color, Grid, GridVector2, Vertex};
+use gameplay::pack::PuzzlePack;
+use gameplay::text;
+use witness_core::*;
+
+/// How many constraints the generator tries before settling for a puzzle with more than one solution
+const GENERATOR_BUDGET: usize = 50;
+/// The biggest puzzles that are generated, in both directions. Puzzles are generated while the game waits for them,
+/// and the generator's solver slows down so fast with size that anything bigger can take minutes
+const MAX_GENERATED_SIZE: usize = 5;
+
+pub const USAGE: &str = "\
+Usage: frontend-sdl2 [PUZZLE_OR_PACK_FILE | OPTION]
+
+Options:
+  --puzzle FILE   Play a puzzle in the text format
+  --pack FILE     Play the puzzles in a pack, starting with the first one
+  --seed NUMBER   Play generated puzzles, starting with the one for this seed
+  --size WxH      Set the size of generated puzzles (default 4x4, at most 5x5)
+  --code CODE     Play a puzzle from a share code of The Windmill
+  --help          Show this message
+
+Files ending in .pack are played as packs, any other file as a single puzzle.";
+
+/// A puzzle to play, and what it's called
+pub struct Puzzle {
+    /// The puzzle's ID in its pack, if it came from one
+    pub id: Option<String>,
+    pub name: String,
+    pub grid: Grid,
+}
+
+/// Where the puzzles that are played come from
+pub enum PuzzleSource {
+    /// A single puzzle, from a file, a share code, or the built-in default
+    Single {
+        name: String,
+        grid: Grid,
+        /// The file the puzzle was loaded from, if it came from one
+        path: Option<String>,
+    },
+    Pack {
+        pack: PuzzlePack,
+        path: String,
+    },
+    /// Generated puzzles, one for every seed counting up from the given one
+    Generator {
+        size: GridVector2,
+        seed: u64,
+    },
+}
+
+/// The puzzle that's played when no puzzle is given on the command line
+fn default_grid() -> Grid {
+    let mut grid = Grid::new(vec2!(2, 4));
+    grid.set_vertex_type(grid.get_vertex_id_at(vec2!(0, 4)).unwrap(), Vertex::Start);
+    grid.set_vertex_type(grid.get_vertex_id_at(vec2!(2, 0)).unwrap(), Vertex::Exit);
+    grid.set_vertex_type(grid.get_vertex_id_at(vec2!(1, 2)).unwrap(), Vertex::Dot);
+    grid.set_cell_type(
+        grid.get_cell_id_at(vec2!(0, 0)).unwrap(),
+        CellType::Square(Color::Black),
+    );
+    grid.set_cell_type(
+        grid.get_cell_id_at(vec2!(1, 0)).unwrap(),
+        CellType::Square(Color::White),
+    );
+    grid
+}
+
+fn load_puzzle_file(path: &str) -> Result<PuzzleSource, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("Couldn't read puzzle {path}: {error}"))?;
+    let grid =
+        text::parse(&text).map_err(|error| format!("Couldn't load puzzle {path}: {error}"))?;
+    Ok(PuzzleSource::Single {
+        name: path.to_string(),
+        grid,
+        path: Some(path.to_string()),
+    })
+}
+
+fn load_pack_file(path: &str) -> Result<PuzzleSource, String> {
+    PuzzlePack::load(path)
+        .map(|pack| PuzzleSource::Pack {
+            pack,
+            path: path.to_string(),
+        })
+        .map_err(|error| format!("Couldn't load pack {path}: {error}"))
+}
+
+/// Reads a size written as `WxH`
+fn parse_size(size: &str) -> Option<GridVector2> {
+    let (width, height) = size.split_once('x')?;
+    let size = vec2!(width.parse().ok()?, height.parse().ok()?);
+    (size.x > 0 && size.y > 0).then_some(size)
+}
+
+impl PuzzleSource {
+    /// Picks where puzzles come from based on the command line arguments, leaving out the program's name.
+    /// Fails with a message to show the user if the arguments are wrong or the puzzles can't be loaded.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        let mut source = None;
+        let mut size = vec2!(4, 4);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("{arg} needs a value\n\n{USAGE}"))
+            };
+            let next = match arg.as_str() {
+                "--puzzle" => load_puzzle_file(&value()?)?,
+                "--pack" => load_pack_file(&value()?)?,
+                "--seed" => {
+                    let seed = value()?;
+                    let seed = seed
+                        .parse()
+                        .map_err(|_| format!("{seed} isn't a valid seed"))?;
+                    PuzzleSource::Generator { size, seed }
+                }
+                "--size" => {
+                    let value = value()?;
+                    size = parse_size(&value)
+                        .ok_or_else(|| format!("{value} isn't a size like 4x4"))?;
+                    if size.x > MAX_GENERATED_SIZE || size.y > MAX_GENERATED_SIZE {
+                        return Err(format!(
+                            "{value} is too big, generated puzzles can be at most {MAX_GENERATED_SIZE}x{MAX_GENERATED_SIZE}"
+                        ));
+                    }
+                    continue;
+                }
+                "--code" => {
+                    let code = value()?;
+                    let grid = Grid::from_share_code(&code)
+                        .map_err(|error| format!("Couldn't decode {code}: {error}"))?;
+                    PuzzleSource::Single {
+                        name: "Shared puzzle".to_string(),
+                        grid,
+                        path: None,
+                    }
+                }
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                option if option.starts_with('-') => {
+                    return Err(format!("Unknown option {option}\n\n{USAGE}"))
+                }
+                path if path.ends_with(".pack") => load_pack_file(path)?,
+                path => load_puzzle_file(path)?,
+            };
+            if source.replace(next).is_some() {
+                return Err(format!(
+                    "Only one puzzle, pack, seed or code can be played at once\n\n{USAGE}"
+                ));
+            }
+        }
+        Ok(match source {
+            // The size may come after the seed
+            Some(PuzzleSource::Generator { seed, .. }) => PuzzleSource::Generator { size, seed },
+            Some(source) => source,
+            None => PuzzleSource::Single {
+                name: "The Witness".to_string(),
+                grid: default_grid(),
+                path: None,
+            },
+        })
+    }
+
+    /// Checks whether there's a puzzle at the given position, without loading or generating it
+    pub fn has_puzzle(&self, index: usize) -> bool {
+        match self {
+            PuzzleSource::Single { .. } => index == 0,
+            PuzzleSource::Pack { pack, .. } => index < pack.puzzles.len(),
+            PuzzleSource::Generator { .. } => true,
+        }
+    }
+
+    /// Gets the file the puzzle was loaded from, if it's a single puzzle that came from a file
+    pub fn get_puzzle_path(&self) -> Option<&str> {
+        match self {
+            PuzzleSource::Single { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Gets the file the puzzles were loaded from, whether it's a puzzle or a pack
+    pub fn get_path(&self) -> Option<&str> {
+        match self {
+            PuzzleSource::Single { path, .. } => path.as_deref(),
+            PuzzleSource::Pack { path, .. } => Some(path),
+            PuzzleSource::Generator { .. } => None,
+        }
+    }
+
+    /// Loads the puzzles again from the file they came from, e.g. after it was changed.
+    /// Fails with a message to show the user if the file can't be loaded anymore, or if there's no file.
+    pub fn reload(&self) -> Result<Self, String> {
+        match self {
+            PuzzleSource::Single {
+                path: Some(path), ..
+            } => load_puzzle_file(path),
+            PuzzleSource::Pack { path, .. } => load_pack_file(path),
+            _ => Err("These puzzles weren't loaded from a file".to_string()),
+        }
+    }
+
+    /// Gets the puzzle at the given position, or `None` if there aren't that many puzzles
+    pub fn get_puzzle(&self, index: usize) -> Option<Puzzle> {
+        match self {
+            PuzzleSource::Single { name, grid, .. } => (index == 0).then(|| Puzzle {
+                id: None,
+                name: name.clone(),
+                grid: grid.clone(),
+            }),
+            PuzzleSource::Pack { pack, .. } => pack.puzzles.get(index).map(|puzzle| Puzzle {
+                id: Some(puzzle.id.clone()),
+                name: puzzle.name.clone(),
+                grid: puzzle.grid.clone(),
+            }),
+            PuzzleSource::Generator { size, seed } => {
+                let seed = seed.wrapping_add(index as u64);
+                Some(Puzzle {
+                    id: None,
+                    name: format!("Seed {seed}"),
+                    grid: Generator::new(*size, seed)
+                        .generate_unique(GENERATOR_BUDGET)
+                        .grid,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the file is checked for changes
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Notices when a file changes, by checking when it was last modified every now and then
+pub struct FileWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+fn get_modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl FileWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        FileWatcher {
+            modified: get_modified_time(&path),
+            path,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Checks whether the file was changed since the last time this returned true.
+    /// The file isn't looked at more often than every `CHECK_INTERVAL`, so this can be called every frame.
+    /// A file that's missing, e.g. while an editor replaces it, only counts as changed once it's back.
+    pub fn has_changed(&mut self) -> bool {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+        match get_modified_time(&self.path) {
+            Some(modified) if Some(modified) != self.modified => {
+                self.modified = Some(modified);
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    grid::{EdgeID, Grid, GridSnapshot},
+    path::Path,
+    solver::Solver,
+};
+
+/// Describes how an edge is used across all solutions of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeUsage {
+    /// Every solution crosses this edge.
+    Forced,
+    /// No solution crosses this edge.
+    Forbidden,
+    /// Some solutions cross this edge and some don't.
+    Free,
+}
+
+/// Which edges of a grid are forced, forbidden or free across all of its solutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeAnalysis {
+    usage: Vec<EdgeUsage>,
+    solution_count: usize,
+}
+
+impl EdgeAnalysis {
+    /// Analyzes every edge of a grid.
+    /// This finds every solution, so it's only practical for grids up to about 5x5.
+    /// If the grid has no solutions at all, every edge is considered forbidden.
+    pub fn new(grid: &Grid) -> Self {
+        let solver = Solver::new(grid);
+        Self::from_solutions(solver.get_snapshot(), &solver.solve(None).solutions)
+    }
+
+    /// Analyzes every edge of a grid like `new`, but gives up if the grid has more than `solution_limit` solutions,
+    /// or if finding them takes the solver more than `node_budget` nodes. See `Solver::solve_within`.
+    /// This keeps the time and memory it takes bounded on grids of any size.
+    pub fn new_within(grid: &Grid, solution_limit: usize, node_budget: usize) -> Option<Self> {
+        let solver = Solver::new(grid);
+        // Looking for one more solution than allowed tells grids with exactly the limit apart from those with more
+        let result = solver.solve_within(Some(solution_limit.saturating_add(1)), node_budget);
+        result
+            .exhaustive
+            .then(|| Self::from_solutions(solver.get_snapshot(), &result.solutions))
+    }
+
+    fn from_solutions(snapshot: &GridSnapshot, solutions: &[Path]) -> Self {
+        let mut times_used = vec![0; snapshot.edge_count()];
+        solutions.iter().for_each(|path| {
+            path.get_edge_ids(snapshot)
+                .unwrap()
+                .into_iter()
+                .for_each(|e| times_used[e] += 1)
+        });
+        let usage = times_used
+            .into_iter()
+            .map(|count| {
+                if count == 0 {
+                    EdgeUsage::Forbidden
+                } else if count == solutions.len() {
+                    EdgeUsage::Forced
+                } else {
+                    EdgeUsage::Free
+                }
+            })
+            .collect();
+        EdgeAnalysis {
+            usage,
+            solution_count: solutions.len(),
+        }
+    }
+
+    /// Gets how the given edge is used, if it exists.
+    pub fn get_usage(&self, id: EdgeID) -> Option<EdgeUsage> {
+        self.usage.get(id).copied()
+    }
+
+    /// Returns an iterator over every edge ID and how that edge is used.
+    /// Edges are listed in the same order as `Grid::get_all_edgerefs_iter`.
+    pub fn get_usage_iter(&self) -> impl Iterator<Item = (EdgeID, EdgeUsage)> + '_ {
+        self.usage.iter().copied().enumerate()
+    }
+
+    /// Gets the amount of solutions the analysis was based on.
+    pub fn get_solution_count(&self) -> usize {
+        self.solution_count
+    }
+}
+
+impl Grid {
+    /// Finds out which edges are used by every solution, by none, or only by some.
+    pub fn analyze_edges(&self) -> EdgeAnalysis {
+        EdgeAnalysis::new(self)
+    }
+
+    /// Analyzes the edges like `analyze_edges`, giving up on grids with too many solutions or paths.
+    /// See `EdgeAnalysis::new_within`.
+    pub fn analyze_edges_within(
+        &self,
+        solution_limit: usize,
+        node_budget: usize,
+    ) -> Option<EdgeAnalysis> {
+        EdgeAnalysis::new_within(self, solution_limit, node_budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{CellType, Color, EdgeType, Vertex};
+
+    #[test]
+    fn analyze_squares() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        let analysis = grid.analyze_edges();
+        assert_eq!(analysis.get_solution_count(), 2);
+        let [middle, top_right, bottom_left, top_left, left] =
+            [(1, 4), (1, 2), (3, 4), (0, 1), (0, 3)]
+                .map(|(a, b)| grid.get_edge_id_between(a, b).unwrap());
+        // Both solutions go through the middle to keep the colors apart
+        assert_eq!(analysis.get_usage(middle), Some(EdgeUsage::Forced));
+        assert_eq!(analysis.get_usage(top_right), Some(EdgeUsage::Free));
+        assert_eq!(analysis.get_usage(bottom_left), Some(EdgeUsage::Free));
+
+        // Cutting off the long way around leaves a single solution
+        grid.set_edge_type(top_left, EdgeType::Gap);
+        let analysis = grid.analyze_edges();
+        assert_eq!(analysis.get_solution_count(), 1);
+        assert_eq!(analysis.get_usage(top_right), Some(EdgeUsage::Forced));
+        assert_eq!(analysis.get_usage(left), Some(EdgeUsage::Forbidden));
+        assert_eq!(analysis.get_usage(top_left), Some(EdgeUsage::Forbidden));
+        assert_eq!(grid.analyze_edges_within(1, 1000), Some(analysis));
+    }
+
+    #[test]
+    fn give_up_on_big_grids() {
+        let mut grid = Grid::new(vec2!(4, 4));
+        grid.set_vertex_type(20, Vertex::Start);
+        grid.set_vertex_type(4, Vertex::Exit);
+        assert_eq!(grid.analyze_edges_within(10, 1_000_000), None);
+        assert_eq!(grid.analyze_edges_within(1_000_000, 100), None);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::grid::{CellID, CellType, EdgeID, EdgeType, Grid, Vertex, VertexID};
+
+/// A single symbol placed on a grid.
+/// Starts, exits and removed vertices are part of the grid's layout rather than symbols, so they aren't constraints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    Vertex(VertexID, Vertex),
+    Edge(EdgeID, EdgeType),
+    Cell(CellID, CellType),
+}
+
+impl Constraint {
+    /// Lists every constraint on a grid: dots, gaps and cell symbols.
+    pub fn get_all(grid: &Grid) -> Vec<Constraint> {
+        let snapshot = grid.snapshot();
+        let vertices = snapshot.get_all_vertex_ids_iter().filter_map(|v| {
+            match snapshot.get_vertex_type(v).unwrap() {
+                Vertex::Dot => Some(Constraint::Vertex(v, Vertex::Dot)),
+                _ => None,
+            }
+        });
+        let edges = snapshot.get_all_edge_ids_iter().filter_map(|e| {
+            match snapshot.get_edge_type(e).unwrap() {
+                EdgeType::Edge => None,
+                kind => Some(Constraint::Edge(e, kind)),
+            }
+        });
+        let cells = snapshot.get_all_cell_ids_iter().filter_map(|c| {
+            match snapshot.get_cell_type(c).unwrap() {
+                CellType::Blank => None,
+                kind => Some(Constraint::Cell(c, kind)),
+            }
+        });
+        vertices.chain(edges).chain(cells).collect()
+    }
+
+    /// Places this constraint on a grid.
+    pub fn apply(&self, grid: &mut Grid) {
+        match *self {
+            Constraint::Vertex(id, kind) => grid.set_vertex_type(id, kind).map(|_| ()),
+            Constraint::Edge(id, kind) => grid.set_edge_type(id, kind).map(|_| ()),
+            Constraint::Cell(id, kind) => grid.set_cell_type(id, kind).map(|_| ()),
+        };
+    }
+
+    /// Removes this constraint from a grid, leaving a plain vertex, edge or blank cell behind.
+    pub fn remove(&self, grid: &mut Grid) {
+        match *self {
+            Constraint::Vertex(id, _) => Constraint::Vertex(id, Vertex::Vertex),
+            Constraint::Edge(id, _) => Constraint::Edge(id, EdgeType::Edge),
+            Constraint::Cell(id, _) => Constraint::Cell(id, CellType::Blank),
+        }
+        .apply(grid)
+    }
+}
@@ -0,0 +1,715 @@
+use crate::{
+    grid::{CellID, CellType, EdgeID, EdgeType, Grid, GridSnapshot, Vertex, VertexID},
+    path::Path,
+};
+
+/// What the deduction solver knows about an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeState {
+    /// It's not known yet whether the line crosses this edge.
+    Unknown,
+    /// The line has to cross this edge.
+    Used,
+    /// The line can't cross this edge.
+    Unused,
+}
+
+/// The rules the deduction solver reasons with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeductionRule {
+    /// The line can't cross a gap.
+    Gap,
+    /// The line can't go to a vertex that doesn't exist.
+    MissingVertex,
+    /// A dot on an edge means the line has to cross that edge.
+    EdgeDot,
+    /// A dot on a vertex with only two ways in or out means the line has to use both.
+    VertexDot,
+    /// Two squares of different colors next to each other need the line between them.
+    DifferentSquares,
+    /// A triangle cell that already has as many sides as it has triangles can't have any more.
+    TrianglesSatisfied,
+    /// A triangle cell that needs every side it has left has to get all of them.
+    TrianglesNeedRemainingSides,
+    /// Three triangles in a corner need both sides around the corner.
+    ThreeTrianglesInCorner,
+    /// The line can't split, so a vertex that already has two used edges can't have any more.
+    NoBranching,
+    /// A line that enters a vertex has to leave it again, so if only one way out is left, it has to be used.
+    LineMustContinue,
+    /// A vertex with only one edge left can't be passed through, so that edge is useless.
+    DeadEnd,
+    /// The start and the exit each have exactly one edge of the line.
+    Endpoint,
+    /// The line can't cross itself, so an edge that would close a loop can't be used.
+    NoLoops,
+}
+
+impl DeductionRule {
+    /// Gets a short, human readable name for this rule.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            DeductionRule::Gap => "Gaps",
+            DeductionRule::MissingVertex => "Missing vertices",
+            DeductionRule::EdgeDot => "Dots on edges",
+            DeductionRule::VertexDot => "Dots on vertices",
+            DeductionRule::DifferentSquares => "Different squares",
+            DeductionRule::TrianglesSatisfied => "Satisfied triangles",
+            DeductionRule::TrianglesNeedRemainingSides => "Hungry triangles",
+            DeductionRule::ThreeTrianglesInCorner => "Three triangles in a corner",
+            DeductionRule::NoBranching => "No branching",
+            DeductionRule::LineMustContinue => "Line must continue",
+            DeductionRule::DeadEnd => "Dead end",
+            DeductionRule::Endpoint => "Start and exit",
+            DeductionRule::NoLoops => "No loops",
+        }
+    }
+}
+
+/// A single step of reasoning: one rule, applied once, deciding one or more edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeductionStep {
+    pub rule: DeductionRule,
+    /// The edges this step decided.
+    pub edges: Vec<EdgeID>,
+    /// What this step decided about those edges; either `Used` or `Unused`.
+    pub state: EdgeState,
+    /// A sentence explaining the step to a player.
+    pub explanation: String,
+}
+
+/// How far deduction alone got.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeductionOutcome {
+    /// Every edge was decided and the line they form solves the puzzle.
+    Solved(Path),
+    /// The rules ran out before the puzzle was solved; solving it needs guessing or reasoning the solver doesn't know.
+    Stuck,
+    /// The rules contradict each other, so the puzzle has no solution. This explains where.
+    Contradiction(String),
+}
+
+/// The result of solving a puzzle by deduction.
+#[derive(Debug, Clone)]
+pub struct Deduction {
+    pub steps: Vec<DeductionStep>,
+    pub outcome: DeductionOutcome,
+    /// What's known about every edge at the end, indexed by edge ID.
+    pub edge_states: Vec<EdgeState>,
+}
+
+impl Deduction {
+    /// Checks whether the puzzle could be solved by deduction alone.
+    pub fn is_solved(&self) -> bool {
+        matches!(self.outcome, DeductionOutcome::Solved(_))
+    }
+
+    /// Counts how many steps used the given rule.
+    pub fn count_rule_uses(&self, rule: DeductionRule) -> usize {
+        self.steps.iter().filter(|step| step.rule == rule).count()
+    }
+}
+
+/// Something a rule says about the grid, before it's checked against what's already known.
+struct Finding {
+    rule: DeductionRule,
+    edges: Vec<EdgeID>,
+    state: EdgeState,
+    explanation: String,
+}
+
+/// Solves puzzles step by step using named rules, the way a person would, without ever guessing.
+#[derive(Debug, Clone)]
+pub struct DeductionSolver {
+    snapshot: GridSnapshot,
+    states: Vec<EdgeState>,
+    steps: Vec<DeductionStep>,
+    /// The start, if the grid only has one.
+    start: Option<VertexID>,
+    /// The exit, if the grid only has one.
+    exit: Option<VertexID>,
+}
+
+impl DeductionSolver {
+    pub fn new(grid: &Grid) -> Self {
+        Self::from_snapshot(grid.snapshot())
+    }
+
+    pub fn from_snapshot(snapshot: GridSnapshot) -> Self {
+        let only = |kind: Vertex| {
+            let mut matching = snapshot
+                .get_all_vertex_ids_iter()
+                .filter(|&v| snapshot.get_vertex_type(v) == Some(kind));
+            match (matching.next(), matching.next()) {
+                (Some(v), None) => Some(v),
+                _ => None,
+            }
+        };
+        DeductionSolver {
+            start: only(Vertex::Start),
+            exit: only(Vertex::Exit),
+            states: vec![EdgeState::Unknown; snapshot.edge_count()],
+            steps: vec![],
+            snapshot,
+        }
+    }
+
+    /// Gets what's currently known about an edge.
+    pub fn get_edge_state(&self, id: EdgeID) -> EdgeState {
+        self.states[id]
+    }
+
+    /// Takes a partial line drawn by the player as given, marking its edges as used.
+    /// The start it begins at can't have any other edges, even if the grid has several starts.
+    /// This isn't recorded as a step. The path should be checked with `GridSnapshot::check_path` first.
+    pub fn assume_path(&mut self, path: &Path) {
+        let Some(edges) = path.get_edge_ids(&self.snapshot) else {
+            return;
+        };
+        if let (Some(first), Some(&first_edge)) = (path.first(), edges.first()) {
+            self.snapshot
+                .get_edges_of_vertex(first)
+                .iter()
+                .filter(|&&e| e != first_edge)
+                .for_each(|&e| self.states[e] = EdgeState::Unused);
+        }
+        edges
+            .into_iter()
+            .for_each(|e| self.states[e] = EdgeState::Used);
+    }
+
+    /// Gets the steps taken so far.
+    pub fn get_steps(&self) -> &Vec<DeductionStep> {
+        &self.steps
+    }
+
+    /// Applies a single rule.
+    /// Returns the step that was taken, `Ok(None)` if no rule applies anymore,
+    /// or an explanation of the contradiction if the puzzle turned out to be unsolvable.
+    pub fn step(&mut self) -> Result<Option<DeductionStep>, String> {
+        if let Some(contradiction) = self.find_contradiction() {
+            return Err(contradiction);
+        }
+        for finding in self.get_findings() {
+            if let Some(&conflict) = finding.edges.iter().find(|&&e| {
+                !matches!(self.states[e], EdgeState::Unknown) && self.states[e] != finding.state
+            }) {
+                return Err(format!(
+                    "{} But {} was already {}.",
+                    finding.explanation,
+                    self.describe_edge(conflict),
+                    if self.states[conflict] == EdgeState::Used {
+                        "used"
+                    } else {
+                        "ruled out"
+                    }
+                ));
+            }
+            let edges: Vec<EdgeID> = finding
+                .edges
+                .iter()
+                .copied()
+                .filter(|&e| self.states[e] == EdgeState::Unknown)
+                .collect();
+            if edges.is_empty() {
+                continue;
+            }
+            edges.iter().for_each(|&e| self.states[e] = finding.state);
+            let step = DeductionStep {
+                rule: finding.rule,
+                edges,
+                state: finding.state,
+                explanation: finding.explanation,
+            };
+            self.steps.push(step.clone());
+            return Ok(Some(step));
+        }
+        Ok(None)
+    }
+
+    /// Applies rules until none apply anymore, then checks whether the puzzle got solved.
+    pub fn solve(mut self) -> Deduction {
+        let outcome = loop {
+            match self.step() {
+                Ok(Some(_)) => {}
+                Ok(None) => break self.get_final_outcome(),
+                Err(contradiction) => break DeductionOutcome::Contradiction(contradiction),
+            }
+        };
+        Deduction {
+            steps: self.steps,
+            outcome,
+            edge_states: self.states,
+        }
+    }
+
+    fn get_final_outcome(&mut self) -> DeductionOutcome {
+        let Some(path) = self.trace_line() else {
+            return DeductionOutcome::Stuck;
+        };
+        // The line is complete, so anything else would have to branch off of it
+        self.states
+            .iter_mut()
+            .filter(|state| **state == EdgeState::Unknown)
+            .for_each(|state| *state = EdgeState::Unused);
+        if self.snapshot.validate(&path).is_ok() {
+            DeductionOutcome::Solved(path)
+        } else {
+            DeductionOutcome::Contradiction(
+                "The only line that's left doesn't satisfy every symbol.".to_string(),
+            )
+        }
+    }
+
+    /// Follows the used edges from a start. Returns the line if it reaches an exit and uses every used edge.
+    fn trace_line(&self) -> Option<Path> {
+        let used_count = self
+            .states
+            .iter()
+            .filter(|&&state| state == EdgeState::Used)
+            .count();
+        let start = self.snapshot.get_all_vertex_ids_iter().find(|&v| {
+            self.snapshot.get_vertex_type(v) == Some(Vertex::Start) && self.get_used_degree(v) == 1
+        })?;
+        let mut path = Path::starting_at(start);
+        let mut previous = None;
+        let mut current = start;
+        loop {
+            let next = self
+                .snapshot
+                .get_edges_of_vertex(current)
+                .iter()
+                .filter(|&&e| self.states[e] == EdgeState::Used)
+                .filter_map(|&e| self.snapshot.get_edge(e).unwrap().get_other_vertex(current))
+                .find(|&v| Some(v) != previous);
+            match next {
+                Some(next) if !path.contains_vertex(next) => {
+                    path.push(next);
+                    previous = Some(current);
+                    current = next;
+                }
+                Some(_) => return None,
+                None => break,
+            }
+        }
+        (self.snapshot.get_vertex_type(current) == Some(Vertex::Exit)
+            && path.len() == used_count + 1)
+            .then_some(path)
+    }
+
+    fn get_used_degree(&self, vertex: VertexID) -> usize {
+        self.snapshot
+            .get_edges_of_vertex(vertex)
+            .iter()
+            .filter(|&&e| self.states[e] == EdgeState::Used)
+            .count()
+    }
+
+    fn get_unknown_edges_of_vertex(&self, vertex: VertexID) -> Vec<EdgeID> {
+        self.snapshot
+            .get_edges_of_vertex(vertex)
+            .iter()
+            .copied()
+            .filter(|&e| self.states[e] == EdgeState::Unknown)
+            .collect()
+    }
+
+    /// Checks whether a vertex must have exactly one edge of the line.
+    fn is_endpoint(&self, vertex: VertexID) -> bool {
+        self.start == Some(vertex) || self.exit == Some(vertex)
+    }
+
+    /// Checks whether the line may end at a vertex, or pass through it.
+    fn is_flexible(&self, vertex: VertexID) -> bool {
+        matches!(
+            self.snapshot.get_vertex_type(vertex),
+            Some(Vertex::Start) | Some(Vertex::Exit)
+        ) && !self.is_endpoint(vertex)
+    }
+
+    fn find_contradiction(&self) -> Option<String> {
+        for vertex in self.snapshot.get_all_vertex_ids_iter() {
+            let used = self.get_used_degree(vertex);
+            let unknown = self.get_unknown_edges_of_vertex(vertex).len();
+            let at = self.describe_vertex(vertex);
+            if used > 2 {
+                return Some(format!("The line would split at {at}."));
+            }
+            if self.is_endpoint(vertex) {
+                if used > 1 {
+                    return Some(format!(
+                        "The line would pass through {at}, but it has to end there."
+                    ));
+                }
+                if used + unknown == 0 {
+                    return Some(format!("The line can't reach {at}."));
+                }
+            } else if !self.is_flexible(vertex) {
+                if used == 1 && unknown == 0 {
+                    return Some(format!("The line would get stuck at {at}."));
+                }
+                if self.snapshot.get_vertex_type(vertex) == Some(Vertex::Dot) && used + unknown < 2
+                {
+                    return Some(format!("The line can't pass through the dot at {at}."));
+                }
+            }
+        }
+        for cell in self.snapshot.get_all_cell_ids_iter() {
+            if let Some(CellType::Triangle(count)) = self.snapshot.get_cell_type(cell) {
+                let (used, unknown) = self.count_cell_sides(cell);
+                if used > count as usize || used + unknown < count as usize {
+                    return Some(format!(
+                        "The triangles in {} can't get the right amount of sides.",
+                        self.describe_cell(cell)
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    fn count_cell_sides(&self, cell: CellID) -> (usize, usize) {
+        let edges = self.snapshot.get_edges_of_cell(cell);
+        (
+            edges
+                .iter()
+                .filter(|&&e| self.states[e] == EdgeState::Used)
+                .count(),
+            edges
+                .iter()
+                .filter(|&&e| self.states[e] == EdgeState::Unknown)
+                .count(),
+        )
+    }
+
+    /// Lists everything the rules have to say about the grid right now, in the order they should be tried.
+    fn get_findings(&self) -> Vec<Finding> {
+        let snapshot = &self.snapshot;
+        let mut findings = vec![];
+
+        let gaps: Vec<EdgeID> = snapshot
+            .get_all_edge_ids_iter()
+            .filter(|&e| snapshot.get_edge_type(e) == Some(EdgeType::Gap))
+            .collect();
+        findings.push(Finding {
+            rule: DeductionRule::Gap,
+            edges: gaps,
+            state: EdgeState::Unused,
+            explanation: "The line can't cross gaps.".to_string(),
+        });
+
+        let missing: Vec<EdgeID> = snapshot
+            .get_all_edge_ids_iter()
+            .filter(|&e| {
+                snapshot
+                    .get_edge(e)
+                    .unwrap()
+                    .get_vertices_array()
+                    .iter()
+                    .any(|&v| snapshot.get_vertex_type(v) == Some(Vertex::None))
+            })
+            .collect();
+        findings.push(Finding {
+            rule: DeductionRule::MissingVertex,
+            edges: missing,
+            state: EdgeState::Unused,
+            explanation: "The line can't go where there are no vertices.".to_string(),
+        });
+
+        let dots: Vec<EdgeID> = snapshot
+            .get_all_edge_ids_iter()
+            .filter(|&e| snapshot.get_edge_type(e) == Some(EdgeType::Dot))
+            .collect();
+        findings.push(Finding {
+            rule: DeductionRule::EdgeDot,
+            edges: dots,
+            state: EdgeState::Used,
+            explanation: "The line has to cross every dot on an edge.".to_string(),
+        });
+
+        snapshot.get_all_edge_ids_iter().for_each(|e| {
+            if let [a, b] = snapshot.get_cells_of_edge(e) {
+                if let (Some(CellType::Square(color_a)), Some(CellType::Square(color_b))) =
+                    (snapshot.get_cell_type(*a), snapshot.get_cell_type(*b))
+                {
+                    if color_a != color_b {
+                        findings.push(Finding {
+                            rule: DeductionRule::DifferentSquares,
+                            edges: vec![e],
+                            state: EdgeState::Used,
+                            explanation: format!(
+                                "The squares in {} and {} have different colors, so the line has to separate them.",
+                                self.describe_cell(*a),
+                                self.describe_cell(*b)
+                            ),
+                        });
+                    }
+                }
+            }
+        });
+
+        snapshot.get_all_cell_ids_iter().for_each(|cell| {
+            let Some(CellType::Triangle(count)) = snapshot.get_cell_type(cell) else {
+                return;
+            };
+            let count = count as usize;
+            let edges = snapshot.get_edges_of_cell(cell);
+            if count == 3 {
+                // A corner vertex that only touches this cell can't be turned around at,
+                // so the line either uses both sides around it or neither
+                for &vertex in snapshot.get_all_vertex_ids_iter().collect::<Vec<_>>().iter() {
+                    let around = snapshot.get_edges_of_vertex(vertex);
+                    if around.len() == 2
+                        && around.iter().all(|e| edges.contains(e))
+                        && !self.is_endpoint(vertex)
+                        && !self.is_flexible(vertex)
+                    {
+                        findings.push(Finding {
+                            rule: DeductionRule::ThreeTrianglesInCorner,
+                            edges: around.to_vec(),
+                            state: EdgeState::Used,
+                            explanation: format!(
+                                "{} has three triangles and sits in the corner at {}. Leaving out either side around the corner would strand the line there, so both have to be used.",
+                                self.describe_cell(cell),
+                                self.describe_vertex(vertex)
+                            ),
+                        });
+                    }
+                }
+            }
+            let (used, unknown) = self.count_cell_sides(cell);
+            let remaining: Vec<EdgeID> = edges
+                .iter()
+                .copied()
+                .filter(|&e| self.states[e] == EdgeState::Unknown)
+                .collect();
+            if used == count && unknown > 0 {
+                findings.push(Finding {
+                    rule: DeductionRule::TrianglesSatisfied,
+                    edges: remaining,
+                    state: EdgeState::Unused,
+                    explanation: format!(
+                        "{} needs {} {} and already has {}, so the line can't touch its other sides.",
+                        self.describe_cell(cell),
+                        count,
+                        if count == 1 { "side" } else { "sides" },
+                        used
+                    ),
+                });
+            } else if used + unknown == count && unknown > 0 {
+                findings.push(Finding {
+                    rule: DeductionRule::TrianglesNeedRemainingSides,
+                    edges: remaining,
+                    state: EdgeState::Used,
+                    explanation: format!(
+                        "{} needs {} {}, and only has {} left that the line could use, so it has to use all of them.",
+                        self.describe_cell(cell),
+                        count,
+                        if count == 1 { "side" } else { "sides" },
+                        used + unknown
+                    ),
+                });
+            }
+        });
+
+        snapshot.get_all_vertex_ids_iter().for_each(|vertex| {
+            let used = self.get_used_degree(vertex);
+            let unknown = self.get_unknown_edges_of_vertex(vertex);
+            if unknown.is_empty() {
+                return;
+            }
+            let at = self.describe_vertex(vertex);
+            if self.is_endpoint(vertex) {
+                if used == 1 {
+                    findings.push(Finding {
+                        rule: DeductionRule::Endpoint,
+                        edges: unknown,
+                        state: EdgeState::Unused,
+                        explanation: format!(
+                            "The line already leaves the end at {at}, so it can't use any of its other edges."
+                        ),
+                    });
+                } else if unknown.len() == 1 {
+                    findings.push(Finding {
+                        rule: DeductionRule::Endpoint,
+                        edges: unknown,
+                        state: EdgeState::Used,
+                        explanation: format!(
+                            "The line has to reach the end at {at}, and there's only one way to get there."
+                        ),
+                    });
+                }
+            } else if used == 2 {
+                findings.push(Finding {
+                    rule: DeductionRule::NoBranching,
+                    edges: unknown,
+                    state: EdgeState::Unused,
+                    explanation: format!(
+                        "The line already goes in and out of {at}, and it can't split."
+                    ),
+                });
+            } else if self.is_flexible(vertex) {
+                // The line might end here, so none of the rules below apply
+            } else if used == 1 && unknown.len() == 1 {
+                findings.push(Finding {
+                    rule: DeductionRule::LineMustContinue,
+                    edges: unknown,
+                    state: EdgeState::Used,
+                    explanation: format!(
+                        "The line comes into {at} and has only one way to leave it."
+                    ),
+                });
+            } else if self.snapshot.get_vertex_type(vertex) == Some(Vertex::Dot)
+                && used == 0
+                && unknown.len() == 2
+            {
+                findings.push(Finding {
+                    rule: DeductionRule::VertexDot,
+                    edges: unknown,
+                    state: EdgeState::Used,
+                    explanation: format!(
+                        "The line has to pass through the dot at {at}, and there are only two ways in and out."
+                    ),
+                });
+            } else if used == 0 && unknown.len() == 1 {
+                findings.push(Finding {
+                    rule: DeductionRule::DeadEnd,
+                    edges: unknown,
+                    state: EdgeState::Unused,
+                    explanation: format!(
+                        "{at} is a dead end; the line could go in but never come back out."
+                    ),
+                });
+            }
+        });
+
+        // Group the vertices into pieces of line that are already known
+        let mut piece: Vec<VertexID> = snapshot.get_all_vertex_ids_iter().collect();
+        fn find(piece: &mut Vec<VertexID>, v: VertexID) -> VertexID {
+            if piece[v] != v {
+                piece[v] = find(piece, piece[v]);
+            }
+            piece[v]
+        }
+        snapshot.get_all_edge_ids_iter().for_each(|e| {
+            if self.states[e] == EdgeState::Used {
+                let [a, b] = snapshot.get_edge(e).unwrap().get_vertices_array();
+                let (a, b) = (find(&mut piece, a), find(&mut piece, b));
+                piece[a] = b;
+            }
+        });
+        snapshot.get_all_edge_ids_iter().for_each(|e| {
+            if self.states[e] == EdgeState::Unknown {
+                let [a, b] = snapshot.get_edge(e).unwrap().get_vertices_array();
+                if find(&mut piece, a) == find(&mut piece, b) {
+                    findings.push(Finding {
+                        rule: DeductionRule::NoLoops,
+                        edges: vec![e],
+                        state: EdgeState::Unused,
+                        explanation: format!(
+                            "The line already connects {} and {}, so using {} would make it run into itself.",
+                            self.describe_vertex(a),
+                            self.describe_vertex(b),
+                            self.describe_edge(e)
+                        ),
+                    });
+                }
+            }
+        });
+
+        findings
+    }
+
+    fn describe_vertex(&self, vertex: VertexID) -> String {
+        let position = self.snapshot.get_vertex_position(vertex).unwrap();
+        format!("({}, {})", position.x, position.y)
+    }
+
+    fn describe_edge(&self, edge: EdgeID) -> String {
+        let [a, b] = self.snapshot.get_edge(edge).unwrap().get_vertices_array();
+        format!(
+            "the edge between {} and {}",
+            self.describe_vertex(a),
+            self.describe_vertex(b)
+        )
+    }
+
+    fn describe_cell(&self, cell: CellID) -> String {
+        let position = self.snapshot.get_cell_position(cell).unwrap();
+        format!("The cell at ({}, {})", position.x, position.y)
+    }
+}
+
+impl Grid {
+    /// Tries to solve this grid by deduction alone, recording every step.
+    pub fn deduce(&self) -> Deduction {
+        DeductionSolver::new(self).solve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Color, TriangleCount};
+
+    #[test]
+    fn deduce_squares_and_gap() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        // With both ways around the middle open, deduction alone can't decide
+        assert_eq!(grid.deduce().outcome, DeductionOutcome::Stuck);
+
+        grid.set_edge_type(grid.get_edge_id_between(0, 1).unwrap(), EdgeType::Gap);
+        let deduction = grid.deduce();
+        assert_eq!(
+            deduction.outcome,
+            DeductionOutcome::Solved(Path::from_vertices(vec![3, 4, 1, 2]))
+        );
+        assert_eq!(deduction.steps[0].rule, DeductionRule::Gap);
+        assert_eq!(
+            deduction.count_rule_uses(DeductionRule::DifferentSquares),
+            1
+        );
+        assert!(deduction
+            .steps
+            .iter()
+            .all(|step| !step.explanation.is_empty()));
+    }
+
+    #[test]
+    fn deduce_three_triangles_in_corner() {
+        let mut grid = Grid::new(vec2!(2, 2));
+        grid.set_vertex_type(6, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Triangle(TriangleCount::Three));
+        let mut solver = DeductionSolver::new(&grid);
+        let step = std::iter::from_fn(|| solver.step().unwrap())
+            .find(|step| step.rule == DeductionRule::ThreeTrianglesInCorner)
+            .unwrap();
+        let mut edges = step.edges.clone();
+        edges.sort();
+        let mut expected = vec![
+            grid.get_edge_id_between(0, 1).unwrap(),
+            grid.get_edge_id_between(0, 3).unwrap(),
+        ];
+        expected.sort();
+        assert_eq!(edges, expected);
+    }
+
+    #[test]
+    fn deduce_contradiction() {
+        let mut grid = Grid::new(vec2!(1, 1));
+        grid.set_vertex_type(2, Vertex::Start);
+        grid.set_vertex_type(1, Vertex::Exit);
+        // Both dots can't be crossed on the way from one corner to the other
+        grid.set_edge_type(grid.get_edge_id_between(0, 1).unwrap(), EdgeType::Dot);
+        grid.set_edge_type(grid.get_edge_id_between(2, 3).unwrap(), EdgeType::Dot);
+        assert!(matches!(
+            grid.deduce().outcome,
+            DeductionOutcome::Contradiction(_)
+        ));
+    }
+}
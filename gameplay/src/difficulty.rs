@@ -0,0 +1,178 @@
+use crate::{
+    grid::{CellType, EdgeType, Grid, GridSnapshot, Vertex},
+    solver::{Solver, SolverStats},
+};
+
+/// Puzzles scoring below this are considered easy.
+pub const EASY_THRESHOLD: f32 = 14.;
+/// Puzzles scoring at or above this are considered hard.
+pub const HARD_THRESHOLD: f32 = 22.;
+
+/// A rough difficulty category, for sorting puzzles into groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DifficultyBucket {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// An estimate of how hard a puzzle is, along with what the estimate was based on.
+#[derive(Debug, Clone, Copy)]
+pub struct Difficulty {
+    /// The overall difficulty score. Higher is harder.
+    /// This has no upper bound, but uniquely solvable puzzles up to 4x4 tend to land between 5 and 30.
+    pub score: f32,
+    /// The statistics of an exhaustive solver run.
+    pub stats: SolverStats,
+    /// How many solutions the puzzle has.
+    pub solution_count: usize,
+    /// How many symbols (dots, gaps and cell symbols) are on the grid.
+    pub symbol_count: usize,
+    /// How many different kinds of symbols are on the grid.
+    pub symbol_kinds: usize,
+}
+
+impl Difficulty {
+    /// Estimates the difficulty of a grid.
+    /// This runs the solver to completion, so it's only practical for grids up to about 5x5.
+    pub fn estimate(grid: &Grid) -> Self {
+        let solver = Solver::new(grid);
+        let result = solver.solve(None);
+        let (symbol_count, symbol_kinds) = count_symbols(solver.get_snapshot());
+        let stats = result.stats;
+        let solution_count = result.solutions.len();
+
+        // How big the search space is; every doubling adds a little
+        let effort = (stats.nodes_visited.max(1) as f32).log2();
+        // Paths that almost work are what trips players up the most
+        let traps = ((stats.near_solutions + 1) as f32).log2();
+        // How far a wrong guess can lead you before you notice, relative to the longest path
+        let backtracking = stats.max_backtrack_depth as f32 / stats.max_depth.max(1) as f32;
+        let branching = (stats.get_branching_factor() - 1.).max(0.);
+
+        let score = (0.5 * effort
+            + 2. * traps
+            + 4. * backtracking
+            + 2. * branching
+            + symbol_kinds as f32
+            + 0.25 * symbol_count as f32)
+            // Puzzles with many solutions are more forgiving
+            / (solution_count.max(1) as f32).sqrt();
+
+        Difficulty {
+            score,
+            stats,
+            solution_count,
+            symbol_count,
+            symbol_kinds,
+        }
+    }
+
+    /// Gets the difficulty category this estimate falls into.
+    pub fn get_bucket(&self) -> DifficultyBucket {
+        if self.score < EASY_THRESHOLD {
+            DifficultyBucket::Easy
+        } else if self.score < HARD_THRESHOLD {
+            DifficultyBucket::Medium
+        } else {
+            DifficultyBucket::Hard
+        }
+    }
+}
+
+/// Counts the symbols on a grid, and how many different kinds of symbols there are.
+fn count_symbols(snapshot: &GridSnapshot) -> (usize, usize) {
+    let vertex_dots = snapshot
+        .get_all_vertex_ids_iter()
+        .filter(|&v| snapshot.get_vertex_type(v) == Some(Vertex::Dot))
+        .count();
+    let edge_dots = snapshot
+        .get_all_edge_ids_iter()
+        .filter(|&e| snapshot.get_edge_type(e) == Some(EdgeType::Dot))
+        .count();
+    let gaps = snapshot
+        .get_all_edge_ids_iter()
+        .filter(|&e| snapshot.get_edge_type(e) == Some(EdgeType::Gap))
+        .count();
+    let mut cell_kinds = [0; 4];
+    snapshot
+        .get_all_cell_ids_iter()
+        .for_each(|c| match snapshot.get_cell_type(c).unwrap() {
+            CellType::Blank => {}
+            CellType::Square(_) => cell_kinds[0] += 1,
+            CellType::Star(_) => cell_kinds[1] += 1,
+            CellType::Triangle(_) => cell_kinds[2] += 1,
+            CellType::Polyomino(_) => cell_kinds[3] += 1,
+        });
+    // Dots on vertices and dots on edges work the same way, so they count as one kind
+    let counts = [vertex_dots + edge_dots, gaps]
+        .into_iter()
+        .chain(cell_kinds)
+        .collect::<Vec<usize>>();
+    (
+        counts.iter().sum(),
+        counts.iter().filter(|&&count| count > 0).count(),
+    )
+}
+
+impl Grid {
+    /// Estimates how hard this grid is to solve.
+    pub fn estimate_difficulty(&self) -> Difficulty {
+        Difficulty::estimate(self)
+    }
+}
+
+/// Sorts items from easiest to hardest, given a way to get the grid out of each item.
+/// Each grid is only estimated once.
+pub fn sort_by_difficulty<T>(items: &mut Vec<T>, get_grid: impl Fn(&T) -> &Grid) {
+    let mut scored: Vec<(f32, T)> = items
+        .drain(..)
+        .map(|item| (get_grid(&item).estimate_difficulty().score, item))
+        .collect();
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+    items.extend(scored.into_iter().map(|(_, item)| item));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generator::Generator, grid::Color};
+
+    #[test]
+    fn symbols_make_puzzles_harder() {
+        let mut easy = Grid::new(vec2!(2, 2));
+        easy.set_vertex_type(6, Vertex::Start);
+        easy.set_vertex_type(2, Vertex::Exit);
+        let mut hard = easy.clone();
+        hard.set_cell_type(0, CellType::Square(Color::Black));
+        hard.set_cell_type(3, CellType::Square(Color::White));
+        hard.set_vertex_type(4, Vertex::Dot);
+        let easy = easy.estimate_difficulty();
+        let hard = hard.estimate_difficulty();
+        assert!(easy.score < hard.score);
+        assert_eq!(easy.symbol_count, 0);
+        assert_eq!(hard.symbol_count, 3);
+        assert_eq!(hard.symbol_kinds, 2);
+    }
+
+    #[test]
+    fn sort_generated_puzzles() {
+        let mut generator = Generator::new(vec2!(3, 3), 11);
+        let mut grids: Vec<Grid> = (0..4).map(|i| generator.generate(i * 3).grid).collect();
+        sort_by_difficulty(&mut grids, |grid| grid);
+        let scores: Vec<f32> = grids
+            .iter()
+            .map(|grid| grid.estimate_difficulty().score)
+            .collect();
+        assert!(scores.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn buckets() {
+        let mut difficulty = Grid::new(vec2!(1, 1)).estimate_difficulty();
+        difficulty.score = 0.;
+        assert_eq!(difficulty.get_bucket(), DifficultyBucket::Easy);
+        difficulty.score = HARD_THRESHOLD;
+        assert_eq!(difficulty.get_bucket(), DifficultyBucket::Hard);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::{
+    grid::{
+        CellID, CellType, Color, EdgeID, EdgeType, Grid, GridPoint, GridVector2, Polyomino,
+        TriangleCount, Vertex, VertexID,
+    },
+    history::{Command, GridEdit, History},
+};
+
+/// How close to a vertex a point has to be to pick it, in grid units.
+pub const VERTEX_PICK_RADIUS: f32 = 0.2;
+/// How close to an edge a point has to be to pick it, in grid units.
+pub const EDGE_PICK_DISTANCE: f32 = 0.15;
+/// The biggest grid the editor makes, in both directions.
+pub const MAX_EDITOR_SIZE: usize = 12;
+/// How many solutions are counted at most while editing, so the count stays quick to update.
+pub const EDITOR_SOLUTION_LIMIT: usize = 100;
+/// How many nodes the solver visits at most while counting solutions, so every edit stays responsive.
+/// Puzzles without solutions make the solver try every path, and the amount of paths grows very fast with the grid.
+pub const EDITOR_NODE_BUDGET: usize = 300_000;
+
+/// The order vertices go through when they're clicked.
+const VERTEX_CYCLE: [Vertex; 5] = [
+    Vertex::Vertex,
+    Vertex::Start,
+    Vertex::Exit,
+    Vertex::Dot,
+    Vertex::None,
+];
+/// The order edges go through when they're clicked.
+const EDGE_CYCLE: [EdgeType; 3] = [EdgeType::Edge, EdgeType::Gap, EdgeType::Dot];
+/// The polyominoes cells go through when they're clicked, as masks like `Polyomino` stores them.
+const POLYOMINO_CYCLE: [(u16, bool); 6] = [
+    (0b1, false),
+    (0b11, true),
+    (0b1_0011, true),
+    (0b111, true),
+    (0b11_0011, false),
+    (0b1_0011_0001, true),
+];
+
+/// How many solutions a puzzle being edited has, as far as the editor could tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolutionCount {
+    /// Every path was looked at, and this is how many of them are solutions.
+    Exact(usize),
+    /// There are at least this many solutions.
+    /// Counting stopped at `EDITOR_SOLUTION_LIMIT`, or ran out of `EDITOR_NODE_BUDGET` before trying every path.
+    AtLeast(usize),
+}
+
+/// Counts a grid's solutions within the editor's limits.
+fn count_solutions(grid: &Grid) -> SolutionCount {
+    let result = grid.solve_within(Some(EDITOR_SOLUTION_LIMIT), EDITOR_NODE_BUDGET);
+    match result.solutions.len() {
+        count if result.exhaustive => SolutionCount::Exact(count),
+        count => SolutionCount::AtLeast(count),
+    }
+}
+
+/// A part of a grid that can be edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditorTarget {
+    Vertex(VertexID),
+    Edge(EdgeID),
+    Cell(CellID),
+}
+
+/// A change made in the editor, which can be undone and redone.
+#[derive(Debug, Clone)]
+pub enum EditorCommand {
+    /// A vertex, edge or cell was changed.
+    Edit(GridEdit),
+    /// The grid was resized. Whatever didn't fit anymore is lost, so the whole grid is remembered from before.
+    Resize { before: Grid, after: Grid },
+}
+
+impl Command for EditorCommand {
+    type Target = Grid;
+
+    fn apply(&self, grid: &mut Grid) {
+        match self {
+            EditorCommand::Edit(edit) => edit.apply(grid),
+            EditorCommand::Resize { after, .. } => *grid = after.clone(),
+        }
+    }
+
+    fn revert(&self, grid: &mut Grid) {
+        match self {
+            EditorCommand::Edit(edit) => edit.revert(grid),
+            EditorCommand::Resize { before, .. } => *grid = before.clone(),
+        }
+    }
+}
+
+/// Gets the item after `current` in a cycle, or before it when going backwards.
+/// Items that aren't in the cycle start it over from the beginning.
+fn step_cycle<T: Copy + PartialEq>(cycle: &[T], current: T, forwards: bool) -> T {
+    match cycle.iter().position(|&item| item == current) {
+        Some(index) if forwards => cycle[(index + 1) % cycle.len()],
+        Some(index) => cycle[(index + cycle.len() - 1) % cycle.len()],
+        None => cycle[0],
+    }
+}
+
+/// Edits a puzzle: resizing it and changing what's on its vertices, edges and cells.
+/// Keeps count of the puzzle's solutions as it changes, and remembers the changes so they can be undone.
+#[derive(Debug, Clone)]
+pub struct PuzzleEditor {
+    grid: Grid,
+    history: History<EditorCommand>,
+    /// The color new squares and stars get.
+    color: Color,
+    solution_count: SolutionCount,
+}
+
+impl PuzzleEditor {
+    pub fn new(grid: Grid) -> Self {
+        PuzzleEditor {
+            solution_count: count_solutions(&grid),
+            grid,
+            history: History::default(),
+            color: Color::Black,
+        }
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// Gets how many solutions the puzzle has, counted up to `EDITOR_SOLUTION_LIMIT`.
+    pub fn get_solution_count(&self) -> SolutionCount {
+        self.solution_count
+    }
+
+    fn update_solution_count(&mut self) {
+        self.solution_count = count_solutions(&self.grid);
+    }
+
+    /// Finds what's at a point in grid space: a vertex if the point is close to one, otherwise an edge
+    /// if it's close to one, otherwise the cell it's in.
+    pub fn get_target_at(&self, point: GridPoint) -> Option<EditorTarget> {
+        let size = self.grid.get_size();
+        if point.x < -VERTEX_PICK_RADIUS
+            || point.y < -VERTEX_PICK_RADIUS
+            || point.x > size.x as f32 + VERTEX_PICK_RADIUS
+            || point.y > size.y as f32 + VERTEX_PICK_RADIUS
+        {
+            return None;
+        }
+        let clamp = |value: f32, max: usize| (value.max(0.) as usize).min(max);
+        let nearest = vec2!(
+            clamp(point.x.round(), size.x),
+            clamp(point.y.round(), size.y)
+        );
+        let offset = point - vec2cast!(nearest, f32);
+        if offset.mag() <= VERTEX_PICK_RADIUS {
+            return self
+                .grid
+                .get_vertex_id_at(nearest)
+                .map(EditorTarget::Vertex);
+        }
+
+        // The cell the point is in, kept inside the grid so points just outside it can still pick its border edges
+        let cell = vec2!(
+            clamp(point.x.floor(), size.x - 1),
+            clamp(point.y.floor(), size.y - 1)
+        );
+        let edge = |from: GridVector2, to: GridVector2| {
+            let from = self.grid.get_vertex_id_at(from)?;
+            let to = self.grid.get_vertex_id_at(to)?;
+            self.grid.get_edge_id_between(from, to)
+        };
+        if offset.y.abs() <= EDGE_PICK_DISTANCE {
+            return edge(vec2!(cell.x, nearest.y), vec2!(cell.x + 1, nearest.y))
+                .map(EditorTarget::Edge);
+        }
+        if offset.x.abs() <= EDGE_PICK_DISTANCE {
+            return edge(vec2!(nearest.x, cell.y), vec2!(nearest.x, cell.y + 1))
+                .map(EditorTarget::Edge);
+        }
+        if point.x < 0. || point.y < 0. || point.x > size.x as f32 || point.y > size.y as f32 {
+            return None;
+        }
+        self.grid.get_cell_id_at(cell).map(EditorTarget::Cell)
+    }
+
+    /// Changes a vertex, edge or cell to the next kind in its cycle, or the previous one when going backwards.
+    /// Squares and stars are given the current color.
+    pub fn cycle(&mut self, target: EditorTarget, forwards: bool) {
+        let cell_cycle = self.get_cell_cycle();
+        let grid = &mut self.grid;
+        let edit = match target {
+            EditorTarget::Vertex(id) => {
+                let Some(current) = grid.get_vertex_type(id) else {
+                    return;
+                };
+                GridEdit::set_vertex_type(grid, id, step_cycle(&VERTEX_CYCLE, current, forwards))
+            }
+            EditorTarget::Edge(id) => {
+                let Some(current) = grid.get_edge_type(id) else {
+                    return;
+                };
+                GridEdit::set_edge_type(grid, id, step_cycle(&EDGE_CYCLE, current, forwards))
+            }
+            EditorTarget::Cell(id) => {
+                let Some(current) = grid.get_cell_type(id) else {
+                    return;
+                };
+                // Symbols of another color count as the current color, so they stay in place in the cycle
+                let current = match current {
+                    CellType::Square(_) => CellType::Square(self.color),
+                    CellType::Star(_) => CellType::Star(self.color),
+                    current => current,
+                };
+                let next = step_cycle(&cell_cycle, current, forwards);
+                GridEdit::set_cell_type(grid, id, next)
+            }
+        };
+        if let Some(edit) = edit.filter(|edit| !edit.is_noop()) {
+            self.history.record(EditorCommand::Edit(edit));
+            self.update_solution_count();
+        }
+    }
+
+    /// Takes back the most recent change. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let undone = self.history.undo(&mut self.grid);
+        if undone {
+            self.update_solution_count();
+        }
+        undone
+    }
+
+    /// Makes the most recently undone change again. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let redone = self.history.redo(&mut self.grid);
+        if redone {
+            self.update_solution_count();
+        }
+        redone
+    }
+
+    /// Gets the order cells go through when they're clicked.
+    fn get_cell_cycle(&self) -> Vec<CellType> {
+        [
+            CellType::Blank,
+            CellType::Square(self.color),
+            CellType::Star(self.color),
+            CellType::Triangle(TriangleCount::One),
+            CellType::Triangle(TriangleCount::Two),
+            CellType::Triangle(TriangleCount::Three),
+        ]
+        .into_iter()
+        .chain(POLYOMINO_CYCLE.iter().map(|&(mask, rotatable)| {
+            CellType::Polyomino(Polyomino::from_mask(mask, rotatable).unwrap())
+        }))
+        .collect()
+    }
+
+    /// Gives the grid a new size, keeping everything that still fits.
+    /// Sizes are kept between 1 and `MAX_EDITOR_SIZE` in both directions.
+    pub fn resize(&mut self, size: GridVector2) {
+        let size = vec2!(
+            size.x.clamp(1, MAX_EDITOR_SIZE),
+            size.y.clamp(1, MAX_EDITOR_SIZE)
+        );
+        if size == self.grid.get_size() {
+            return;
+        }
+        let old = &self.grid;
+        let mut grid = Grid::new(size);
+        for x in 0..=size.x {
+            for y in 0..=size.y {
+                let position = vec2!(x, y);
+                let (Some(from), Some(to)) = (
+                    old.get_vertex_id_at(position),
+                    grid.get_vertex_id_at(position),
+                ) else {
+                    continue;
+                };
+                grid.set_vertex_type(to, old.get_vertex_type(from).unwrap());
+                if let Some(to_cell) = grid.get_cell_id_at(position) {
+                    if let Some(from_cell) = old.get_cell_id_at(position) {
+                        grid.set_cell_type(to_cell, old.get_cell_type(from_cell).unwrap());
+                    }
+                }
+                // The edges to the right of and below the vertex
+                for neighbour in [vec2!(x + 1, y), vec2!(x, y + 1)] {
+                    let old_edge = old
+                        .get_vertex_id_at(neighbour)
+                        .and_then(|other| old.get_edge_id_between(from, other));
+                    let new_edge = grid
+                        .get_vertex_id_at(neighbour)
+                        .and_then(|other| grid.get_edge_id_between(to, other));
+                    if let (Some(old_edge), Some(new_edge)) = (old_edge, new_edge) {
+                        grid.set_edge_type(new_edge, old.get_edge_type(old_edge).unwrap());
+                    }
+                }
+            }
+        }
+        let before = std::mem::replace(&mut self.grid, grid);
+        self.history.record(EditorCommand::Resize {
+            before,
+            after: self.grid.clone(),
+        });
+        self.update_solution_count();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_targets() {
+        let editor = PuzzleEditor::new(Grid::new(vec2!(2, 1)));
+        let grid = editor.get_grid();
+        let vertex = |x, y| grid.get_vertex_id_at(vec2!(x, y)).unwrap();
+        assert_eq!(
+            editor.get_target_at(vec2!(1.1, 0.95)),
+            Some(EditorTarget::Vertex(vertex(1, 1)))
+        );
+        assert_eq!(
+            editor.get_target_at(vec2!(1.5, -0.1)),
+            Some(EditorTarget::Edge(
+                grid.get_edge_id_between(vertex(1, 0), vertex(2, 0))
+                    .unwrap()
+            ))
+        );
+        assert_eq!(
+            editor.get_target_at(vec2!(2.05, 0.5)),
+            Some(EditorTarget::Edge(
+                grid.get_edge_id_between(vertex(2, 0), vertex(2, 1))
+                    .unwrap()
+            ))
+        );
+        assert_eq!(
+            editor.get_target_at(vec2!(1.5, 0.5)),
+            Some(EditorTarget::Cell(
+                grid.get_cell_id_at(vec2!(1, 0)).unwrap()
+            ))
+        );
+        assert_eq!(editor.get_target_at(vec2!(-1., 0.5)), None);
+    }
+
+    #[test]
+    fn cycles_and_counts_solutions() {
+        let mut editor = PuzzleEditor::new(Grid::new(vec2!(1, 1)));
+        assert_eq!(editor.get_solution_count(), SolutionCount::Exact(0));
+        editor.cycle(EditorTarget::Vertex(2), true);
+        editor.cycle(EditorTarget::Vertex(1), true);
+        editor.cycle(EditorTarget::Vertex(1), true);
+        assert_eq!(editor.get_grid().get_vertex_type(2), Some(Vertex::Start));
+        assert_eq!(editor.get_grid().get_vertex_type(1), Some(Vertex::Exit));
+        assert_eq!(editor.get_solution_count(), SolutionCount::Exact(2));
+
+        editor.set_color(Color::Red);
+        editor.cycle(EditorTarget::Cell(0), true);
+        assert_eq!(
+            editor.get_grid().get_cell_type(0),
+            Some(CellType::Square(Color::Red))
+        );
+        editor.set_color(Color::Blue);
+        editor.cycle(EditorTarget::Cell(0), true);
+        assert_eq!(
+            editor.get_grid().get_cell_type(0),
+            Some(CellType::Star(Color::Blue))
+        );
+        editor.cycle(EditorTarget::Cell(0), false);
+        editor.cycle(EditorTarget::Cell(0), false);
+        editor.cycle(EditorTarget::Cell(0), false);
+        assert!(matches!(
+            editor.get_grid().get_cell_type(0),
+            Some(CellType::Polyomino(_))
+        ));
+
+        let edge = editor.get_grid().get_edge_id_between(0, 1).unwrap();
+        editor.cycle(EditorTarget::Edge(edge), false);
+        assert_eq!(editor.get_grid().get_edge_type(edge), Some(EdgeType::Dot));
+    }
+
+    #[test]
+    fn resizes_keeping_what_fits() {
+        let mut grid = Grid::new(vec2!(2, 2));
+        grid.set_vertex_type(grid.get_vertex_id_at(vec2!(0, 2)).unwrap(), Vertex::Start);
+        grid.set_vertex_type(grid.get_vertex_id_at(vec2!(1, 0)).unwrap(), Vertex::Exit);
+        let gap = grid.get_edge_id_between(0, 1).unwrap();
+        grid.set_edge_type(gap, EdgeType::Gap);
+        grid.set_cell_type(
+            grid.get_cell_id_at(vec2!(1, 1)).unwrap(),
+            CellType::Star(Color::Green),
+        );
+        let mut editor = PuzzleEditor::new(grid);
+
+        editor.resize(vec2!(3, 1));
+        let grid = editor.get_grid();
+        assert_eq!(grid.get_size(), vec2!(3, 1));
+        assert_eq!(
+            grid.get_vertex_type(grid.get_vertex_id_at(vec2!(1, 0)).unwrap()),
+            Some(Vertex::Exit)
+        );
+        let gap = grid.get_edge_id_between(0, 1).unwrap();
+        assert_eq!(grid.get_edge_type(gap), Some(EdgeType::Gap));
+        let snapshot = grid.snapshot();
+        assert!(snapshot
+            .get_all_cell_ids_iter()
+            .all(|cell| snapshot.get_cell_type(cell) == Some(CellType::Blank)));
+        assert!(!grid
+            .get_all_vertex_ids_iter()
+            .any(|vertex| grid.get_vertex_type(vertex) == Some(Vertex::Start)));
+
+        editor.resize(vec2!(0, 40));
+        assert_eq!(editor.get_grid().get_size(), vec2!(1, MAX_EDITOR_SIZE));
+    }
+
+    #[test]
+    fn undoes_and_redoes_edits() {
+        let mut editor = PuzzleEditor::new(Grid::new(vec2!(2, 2)));
+        editor.cycle(EditorTarget::Cell(0), true);
+        editor.resize(vec2!(1, 1));
+        // Resizing to the same size changes nothing, so there's nothing to undo for it
+        editor.resize(vec2!(1, 1));
+        assert_eq!(
+            editor.get_grid().get_cell_type(0),
+            Some(CellType::Square(Color::Black))
+        );
+
+        assert!(editor.undo());
+        assert_eq!(editor.get_grid().get_size(), vec2!(2, 2));
+        assert!(editor.undo());
+        assert_eq!(editor.get_grid().get_cell_type(0), Some(CellType::Blank));
+        assert!(!editor.undo());
+
+        assert!(editor.redo());
+        assert!(editor.redo());
+        assert_eq!(editor.get_grid().get_size(), vec2!(1, 1));
+        assert_eq!(
+            editor.get_grid().get_cell_type(0),
+            Some(CellType::Square(Color::Black))
+        );
+        assert!(!editor.redo());
+    }
+
+    #[test]
+    fn stops_counting_big_puzzles() {
+        // A lone star can never be satisfied, so every path has to be tried to be sure there's no solution
+        let mut grid = Grid::new(vec2!(MAX_EDITOR_SIZE, MAX_EDITOR_SIZE));
+        grid.set_vertex_type(
+            grid.get_vertex_id_at(vec2!(0, MAX_EDITOR_SIZE)).unwrap(),
+            Vertex::Start,
+        );
+        grid.set_vertex_type(
+            grid.get_vertex_id_at(vec2!(MAX_EDITOR_SIZE, 0)).unwrap(),
+            Vertex::Exit,
+        );
+        grid.set_cell_type(0, CellType::Star(Color::White));
+        let editor = PuzzleEditor::new(grid);
+        assert_eq!(editor.get_solution_count(), SolutionCount::AtLeast(0));
+    }
+}
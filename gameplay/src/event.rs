@@ -0,0 +1,87 @@
+use std::collections::VecDeque;
+
+use crate::{grid::VertexID, tracer::TraceBlocker, validation::FailingSymbol};
+
+/// Something that happened during play that a frontend might want to react to,
+/// with a sound, an animation or a rumble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameEvent {
+    /// A line was started at this start.
+    LineStarted(VertexID),
+    /// The line was extended to this vertex.
+    LineExtended(VertexID),
+    /// The line was backed up off of this vertex.
+    LineRetracted(VertexID),
+    /// The line ran into something and stopped.
+    /// This is only sent once each time the line gets stuck, not every time it's pushed against the same thing.
+    LineBlocked(TraceBlocker),
+    /// The line reached the end of the nub of this exit, so letting go of it now submits it.
+    ReachedExit(VertexID),
+    /// The line was let go of at an exit and is being checked.
+    LineSubmitted,
+    /// The line was let go of or thrown away before it reached an exit.
+    LineAbandoned,
+    /// The submitted line solves the puzzle.
+    Solved,
+    /// The submitted line doesn't solve the puzzle. This is followed by a `SymbolFailed` for every failing symbol.
+    Failed,
+    /// This symbol isn't satisfied by the submitted line.
+    SymbolFailed(FailingSymbol),
+}
+
+/// Events waiting for the frontend to handle them, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct EventQueue {
+    events: VecDeque<GameEvent>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: GameEvent) {
+        self.events.push_back(event);
+    }
+
+    /// Takes the oldest event out of the queue.
+    pub fn pop(&mut self) -> Option<GameEvent> {
+        self.events.pop_front()
+    }
+
+    /// Takes every event out of the queue, oldest first.
+    pub fn drain(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.events.drain(..)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_come_out_in_order() {
+        let mut queue = EventQueue::new();
+        queue.push(GameEvent::LineStarted(3));
+        queue.push(GameEvent::LineExtended(4));
+        queue.push(GameEvent::LineAbandoned);
+        assert_eq!(queue.pop(), Some(GameEvent::LineStarted(3)));
+        assert_eq!(
+            queue.drain().collect::<Vec<_>>(),
+            vec![GameEvent::LineExtended(4), GameEvent::LineAbandoned]
+        );
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,331 @@
+use crate::{
+    constraint::Constraint,
+    grid::{
+        CellID, CellType, Color, EdgeType, Grid, GridSnapshot, GridVector2, TriangleCount, Vertex,
+        VertexID,
+    },
+    path::Path,
+    random::Random,
+};
+
+/// Solutions are only counted up to this amount while generating.
+/// Anything above it is treated as "a lot", which is all the generator needs to know.
+pub const SOLUTION_COUNT_LIMIT: usize = 32;
+
+/// How many steps the random walk that picks the intended solution may take before it settles for a shorter path.
+const WALK_BUDGET: usize = 10_000;
+
+/// The colors the generator picks from for squares and stars.
+const PALETTE: [Color; 5] = [
+    Color::Black,
+    Color::White,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+];
+
+/// Describes how generating a puzzle went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GenerationReport {
+    /// The amount of times the generator tried adding a constraint.
+    pub iterations: usize,
+    /// The amount of constraints that were taken away again because they didn't rule out any solutions.
+    pub removed: usize,
+    /// The amount of solutions the finished puzzle has, counted up to `SOLUTION_COUNT_LIMIT`.
+    pub solution_count: usize,
+}
+
+impl GenerationReport {
+    /// Checks whether the finished puzzle has exactly one solution.
+    pub fn is_unique(&self) -> bool {
+        self.solution_count == 1
+    }
+}
+
+/// A puzzle made by the generator, together with the solution it was built around.
+#[derive(Debug, Clone)]
+pub struct GeneratedPuzzle {
+    pub grid: Grid,
+    pub solution: Path,
+    pub report: GenerationReport,
+}
+
+/// Makes random puzzles.
+/// Puzzles are built around a randomly drawn solution, so every generated puzzle is solvable.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    size: GridVector2,
+    random: Random,
+}
+
+impl Generator {
+    /// Creates a generator for puzzles of the given size.
+    /// The same seed always produces the same puzzles.
+    pub fn new(size: GridVector2, seed: u64) -> Self {
+        Generator {
+            size,
+            random: Random::new(seed),
+        }
+    }
+
+    /// Generates a grid with a start and an exit, but no symbols, along with a random path between them.
+    pub fn generate_blank(&mut self) -> (Grid, Path) {
+        let mut grid = Grid::new(self.size);
+        let border: Vec<VertexID> = grid
+            .get_all_vertex_ids_iter()
+            .filter(|&v| {
+                let position = grid.get_vertex_position(v).unwrap();
+                position.x == 0
+                    || position.y == 0
+                    || position.x == self.size.x
+                    || position.y == self.size.y
+            })
+            .collect();
+        let exit = *self.random.choose(&border).unwrap();
+        let start = loop {
+            let start = self.random.below(grid.get_all_vertex_ids_iter().count());
+            if start != exit {
+                break start;
+            }
+        };
+        grid.set_vertex_type(start, Vertex::Start);
+        grid.set_vertex_type(exit, Vertex::Exit);
+        let solution = self.random_walk(&grid.snapshot(), start, exit);
+        (grid, solution)
+    }
+
+    /// Finds a random path from `start` to `exit`, preferring paths that wander around the grid a bit.
+    fn random_walk(&mut self, snapshot: &GridSnapshot, start: VertexID, exit: VertexID) -> Path {
+        let wanted_length = (self.size.x + self.size.y).max(2);
+        let mut path = Path::starting_at(start);
+        let mut visited = vec![false; snapshot.vertex_count()];
+        visited[start] = true;
+        // Each entry holds the neighbours of the corresponding path vertex that haven't been tried yet
+        let mut untried = vec![self.shuffled_neighbours(snapshot, start)];
+        let mut best: Option<Path> = None;
+        let mut steps = 0;
+        while let Some(options) = untried.last_mut() {
+            steps += 1;
+            match options.pop() {
+                Some(next) if !visited[next] => {
+                    visited[next] = true;
+                    path.push(next);
+                    if next == exit {
+                        if path.len() >= wanted_length || steps > WALK_BUDGET {
+                            return path;
+                        }
+                        if best.as_ref().is_none_or(|s| s.len() < path.len()) {
+                            best = Some(path.clone());
+                        }
+                        visited[next] = false;
+                        path.pop();
+                    } else {
+                        untried.push(self.shuffled_neighbours(snapshot, next));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    untried.pop();
+                    if let Some(vertex) = path.pop() {
+                        visited[vertex] = false;
+                    }
+                    if steps > WALK_BUDGET && best.is_some() {
+                        break;
+                    }
+                }
+            }
+        }
+        best.expect("The start and exit of a generated grid are always connected")
+    }
+
+    fn shuffled_neighbours(&mut self, snapshot: &GridSnapshot, vertex: VertexID) -> Vec<VertexID> {
+        let mut neighbours: Vec<VertexID> = snapshot
+            .get_neighbours_iter(vertex)
+            .map(|(_, v)| v)
+            .collect();
+        self.random.shuffle(&mut neighbours);
+        neighbours
+    }
+
+    /// Lists every constraint that could be added to the grid without breaking the given solution.
+    /// Stars come in pairs, so each option is a group of constraints that has to be placed together.
+    fn get_candidate_constraints(&mut self, grid: &Grid, solution: &Path) -> Vec<Vec<Constraint>> {
+        let snapshot = grid.snapshot();
+        let used_edges = solution.get_used_edges(&snapshot);
+        let mut candidates: Vec<Vec<Constraint>> = vec![];
+
+        solution.get_vertices().iter().for_each(|&v| {
+            if snapshot.get_vertex_type(v) == Some(Vertex::Vertex) {
+                candidates.push(vec![Constraint::Vertex(v, Vertex::Dot)]);
+            }
+        });
+        snapshot.get_all_edge_ids_iter().for_each(|e| {
+            if snapshot.get_edge_type(e) == Some(EdgeType::Edge) {
+                let kind = if used_edges[e] {
+                    EdgeType::Dot
+                } else {
+                    EdgeType::Gap
+                };
+                candidates.push(vec![Constraint::Edge(e, kind)]);
+            }
+        });
+
+        let regions = snapshot.get_regions(&used_edges);
+        let square_color_of_region: Vec<Option<Color>> = regions
+            .iter()
+            .map(|region| {
+                region
+                    .iter()
+                    .find_map(|&c| match snapshot.get_cell_type(c) {
+                        Some(CellType::Square(color)) => Some(color),
+                        _ => None,
+                    })
+            })
+            .collect();
+        regions.iter().enumerate().for_each(|(index, region)| {
+            let blank: Vec<CellID> = region
+                .iter()
+                .copied()
+                .filter(|&c| snapshot.get_cell_type(c) == Some(CellType::Blank))
+                .collect();
+            let square_color = square_color_of_region[index].unwrap_or_else(|| {
+                // Prefer a color no other region uses yet, so the squares actually separate something
+                let unused: Vec<Color> = PALETTE
+                    .iter()
+                    .copied()
+                    .filter(|color| !square_color_of_region.contains(&Some(*color)))
+                    .collect();
+                *self
+                    .random
+                    .choose(&unused)
+                    .unwrap_or(&PALETTE[self.random.below(PALETTE.len())])
+            });
+            blank.iter().for_each(|&c| {
+                candidates.push(vec![Constraint::Cell(c, CellType::Square(square_color))]);
+                let sides = snapshot
+                    .get_edges_of_cell(c)
+                    .iter()
+                    .filter(|&&e| used_edges[e])
+                    .count();
+                if let Ok(count) = TriangleCount::try_from(sides as u8) {
+                    candidates.push(vec![Constraint::Cell(c, CellType::Triangle(count))]);
+                }
+            });
+            if blank.len() >= 2 {
+                let star_color = PALETTE[self.random.below(PALETTE.len())];
+                let mut pair = blank.clone();
+                self.random.shuffle(&mut pair);
+                candidates.push(vec![
+                    Constraint::Cell(pair[0], CellType::Star(star_color)),
+                    Constraint::Cell(pair[1], CellType::Star(star_color)),
+                ]);
+            }
+        });
+        candidates
+    }
+
+    /// Adds a random group of constraints to the grid that the given solution still satisfies.
+    /// Returns the constraints that were added, or `None` if there's nothing left that fits.
+    pub fn add_random_constraint(
+        &mut self,
+        grid: &mut Grid,
+        solution: &Path,
+    ) -> Option<Vec<Constraint>> {
+        let mut candidates = self.get_candidate_constraints(grid, solution);
+        self.random.shuffle(&mut candidates);
+        candidates.into_iter().find(|group| {
+            group.iter().for_each(|c| c.apply(grid));
+            if grid.validate(solution).is_ok() {
+                true
+            } else {
+                group.iter().for_each(|c| c.remove(grid));
+                false
+            }
+        })
+    }
+
+    /// Generates a puzzle with the given amount of randomly placed constraint groups.
+    /// The puzzle is guaranteed to be solvable, but it may have many solutions.
+    pub fn generate(&mut self, constraint_count: usize) -> GeneratedPuzzle {
+        let (mut grid, solution) = self.generate_blank();
+        let mut report = GenerationReport::default();
+        while report.iterations < constraint_count {
+            report.iterations += 1;
+            if self.add_random_constraint(&mut grid, &solution).is_none() {
+                break;
+            }
+        }
+        report.solution_count = grid.count_solutions(SOLUTION_COUNT_LIMIT);
+        GeneratedPuzzle {
+            grid,
+            solution,
+            report,
+        }
+    }
+
+    /// Generates a puzzle and keeps adding constraints until it has exactly one solution.
+    /// Constraints that don't rule out any solutions are removed again, so the puzzle doesn't get cluttered.
+    /// At most `budget` constraints are tried; check the report to see whether the result is actually unique.
+    pub fn generate_unique(&mut self, budget: usize) -> GeneratedPuzzle {
+        let (mut grid, solution) = self.generate_blank();
+        let mut report = GenerationReport {
+            solution_count: grid.count_solutions(SOLUTION_COUNT_LIMIT),
+            ..Default::default()
+        };
+        while report.solution_count > 1 && report.iterations < budget {
+            report.iterations += 1;
+            let Some(added) = self.add_random_constraint(&mut grid, &solution) else {
+                break;
+            };
+            let count = grid.count_solutions(SOLUTION_COUNT_LIMIT);
+            if count < report.solution_count || count == SOLUTION_COUNT_LIMIT {
+                report.solution_count = count;
+            } else {
+                added.iter().for_each(|c| c.remove(&mut grid));
+                report.removed += 1;
+            }
+        }
+        GeneratedPuzzle {
+            grid,
+            solution,
+            report,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_puzzles_are_solvable() {
+        let mut generator = Generator::new(vec2!(3, 3), 42);
+        (0..5).for_each(|_| {
+            let puzzle = generator.generate(6);
+            assert_eq!(puzzle.grid.validate(&puzzle.solution), Ok(()));
+            assert!(puzzle.report.solution_count >= 1);
+        });
+    }
+
+    #[test]
+    fn generate_unique_puzzles() {
+        let mut generator = Generator::new(vec2!(3, 3), 7);
+        (0..5).for_each(|_| {
+            let puzzle = generator.generate_unique(200);
+            assert!(puzzle.report.iterations <= 200);
+            // These seeds all find a unique puzzle within the iterations they're given
+            assert!(puzzle.report.is_unique(), "{:?}", puzzle.report);
+            assert_eq!(puzzle.grid.validate(&puzzle.solution), Ok(()));
+            assert_eq!(puzzle.grid.solve(None).solutions, vec![puzzle.solution]);
+        });
+    }
+
+    #[test]
+    fn same_seed_same_puzzle() {
+        let a = Generator::new(vec2!(3, 2), 3).generate_unique(100);
+        let b = Generator::new(vec2!(3, 2), 3).generate_unique(100);
+        assert_eq!(a.solution, b.solution);
+        assert_eq!(a.report, b.report);
+        assert!(a.report.is_unique(), "{:?}", a.report);
+    }
+}
@@ -1,9 +1,13 @@
 use std::{rc::Rc, sync::RwLock};
 
-use super::{color::Color, EdgeRef, VertexID};
+use super::{color::Color, EdgeRef, Polyomino, VertexID};
 
 pub type CellRef = Rc<RwLock<Cell>>;
 
+/// The identifier for a cell.
+/// This is the cell's index in the grid's list of cells.
+pub type CellID = usize;
+
 #[derive(Debug, Clone)]
 pub struct Cell {
     edges: Vec<EdgeRef>,
@@ -23,8 +27,8 @@ impl Cell {
         }
         if edges
             .iter()
-            .fold(Some(edges[0].clone()), |acc: Option<EdgeRef>, e| {
-                if acc?.read().unwrap().connects_to_edge(e) {
+            .try_fold(edges[0].clone(), |acc: EdgeRef, e| {
+                if acc.read().unwrap().connects_to_edge(e) {
                     Some(e.clone())
                 } else {
                     None
@@ -84,7 +88,8 @@ impl Cell {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CellType {
     /// This is a blank cell.
     Blank,
@@ -95,12 +100,26 @@ pub enum CellType {
     Star(Color),
     /// Triangle
     Triangle(TriangleCount),
+    /// This is a cell with a polyomino in it. The region this cell is in has to be exactly covered by all of the
+    /// polyominoes in it, without any of them overlapping.
+    Polyomino(Polyomino),
+}
+
+impl CellType {
+    /// Returns the color of the symbol in this cell, if it has one.
+    pub fn get_color(&self) -> Option<Color> {
+        match self {
+            CellType::Square(color) | CellType::Star(color) => Some(*color),
+            _ => None,
+        }
+    }
 }
 
 /// This enum only has three possible values, for the three possible triangle sets that can be in a cell.
 /// This helps with ensuring type safety of triangles, by limiting the possible values.
 /// This can be cast into a number value by using the `as` keyword.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TriangleCount {
     One = 1,
     Two = 2,
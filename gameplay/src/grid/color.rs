@@ -1,4 +1,5 @@
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Black,
     White,
@@ -8,5 +9,5 @@ pub enum Color {
     Red,
     Green,
     Blue,
-    Orange
+    Orange,
 }
@@ -4,11 +4,15 @@ use super::VertexID;
 
 pub type EdgeRef = Rc<RwLock<Edge>>;
 
+/// The identifier for an edge.
+/// This is the edge's index in the grid's list of edges.
+pub type EdgeID = usize;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Edge {
     from: VertexID,
     to: VertexID,
-    kind: EdgeType
+    kind: EdgeType,
 }
 
 impl Edge {
@@ -38,7 +42,7 @@ impl Edge {
         let edge = edgeref.read().unwrap();
         edge.connects_to(self.from) || edge.connects_to(self.to)
     }
-    
+
     /// Returns which vertex connects this edge and some other edge, if any.
     pub fn which_vertex_connects(&self, edgeref: &EdgeRef) -> Option<VertexID> {
         let edge = edgeref.read().unwrap();
@@ -72,10 +76,21 @@ impl Edge {
     pub fn get_vertices_array(&self) -> [VertexID; 2] {
         [self.from, self.to]
     }
+
+    /// Gets the kind of this edge.
+    pub fn get_kind(&self) -> EdgeType {
+        self.kind
+    }
+
+    /// Changes the kind of this edge, returning the kind it had before.
+    pub fn set_kind(&mut self, kind: EdgeType) -> EdgeType {
+        std::mem::replace(&mut self.kind, kind)
+    }
 }
 
 /// Describes the kind of edge it's attached to.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdgeType {
     /// This is an edge that's mostly present but has a gap in the middle so you can't fully cross it; a gap in the road.
     Gap,
@@ -1,21 +1,31 @@
 pub mod cell;
 pub mod color;
 pub mod edge;
+pub mod polyomino;
+#[cfg(feature = "serde")]
+pub mod schema;
+pub mod snapshot;
 pub mod vertex;
 
 use std::{collections::VecDeque, rc::Rc, sync::RwLock};
 
 use witness_core::{Abs, Vector2};
 
-pub use cell::{Cell, CellRef};
+pub use cell::{Cell, CellID, CellRef, CellType, TriangleCount};
 pub use color::Color;
-pub use edge::{Edge, EdgeRef};
+pub use edge::{Edge, EdgeID, EdgeRef, EdgeType};
+pub use polyomino::Polyomino;
+pub use snapshot::GridSnapshot;
 pub use vertex::{Vertex, VertexID};
 
 /// The unit of measurement for grid coordinates.
 /// This is currently a `Vector2<usize>`
 pub type GridVector2 = Vector2<usize>;
 
+/// A point anywhere on the grid, in the same units as `GridVector2`.
+/// Vertices sit at whole coordinates and everything in between is fractional.
+pub type GridPoint = Vector2<f32>;
+
 pub trait Vector2Extensions {
     fn is_immediately_next_to(&self, other: &Self) -> bool;
 }
@@ -92,12 +102,15 @@ impl Grid {
                         }
                     }
                 });
-                cells.push(Cell::new(vec![
-                    top_edge.unwrap(),
-                    right_edge.unwrap(),
-                    bottom_edge.unwrap(),
-                    left_edge.unwrap()
-                ]).unwrap());
+                cells.push(
+                    Cell::new(vec![
+                        top_edge.unwrap(),
+                        right_edge.unwrap(),
+                        bottom_edge.unwrap(),
+                        left_edge.unwrap(),
+                    ])
+                    .unwrap(),
+                );
             })
         });
         let cells = cells.into_iter().map(|x| Rc::new(RwLock::new(x))).collect();
@@ -141,6 +154,107 @@ impl Grid {
         }
     }
 
+    /// Changes the type of the vertex at the given position, returning the type it had before.
+    /// Returns `None` and does nothing if the vertex doesn't exist.
+    pub fn set_vertex_type(&mut self, id: VertexID, kind: Vertex) -> Option<Vertex> {
+        if self.vertex_exists(id) {
+            Some(std::mem::replace(&mut self.vertices[id], kind))
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether an edge exists.
+    pub fn edge_exists(&self, id: EdgeID) -> bool {
+        id < self.edges.len()
+    }
+
+    /// Returns the edge with the given ID, if it exists.
+    pub fn get_edge(&self, id: EdgeID) -> Option<EdgeRef> {
+        self.edges.get(id).cloned()
+    }
+
+    /// Returns the ID of the given edge, if it's part of this grid.
+    pub fn get_edge_id(&self, edge: &EdgeRef) -> Option<EdgeID> {
+        self.edges.iter().position(|e| EdgeRef::ptr_eq(e, edge))
+    }
+
+    /// Returns the ID of the edge connecting two vertices, if there is one.
+    pub fn get_edge_id_between(&self, a: VertexID, b: VertexID) -> Option<EdgeID> {
+        self.edges.iter().position(|e| {
+            let edge = e.read().unwrap();
+            edge.connects_to(a) && edge.connects_to(b)
+        })
+    }
+
+    /// Returns the type of the edge with the given ID, if it exists.
+    pub fn get_edge_type(&self, id: EdgeID) -> Option<EdgeType> {
+        self.edges.get(id).map(|e| e.read().unwrap().get_kind())
+    }
+
+    /// Changes the type of the edge with the given ID, returning the type it had before.
+    /// Returns `None` and does nothing if the edge doesn't exist.
+    pub fn set_edge_type(&mut self, id: EdgeID, kind: EdgeType) -> Option<EdgeType> {
+        self.edges
+            .get(id)
+            .map(|e| e.write().unwrap().set_kind(kind))
+    }
+
+    /// Checks whether a cell exists.
+    pub fn cell_exists(&self, id: CellID) -> bool {
+        id < self.cells.len()
+    }
+
+    /// Returns the cell with the given ID, if it exists.
+    pub fn get_cell(&self, id: CellID) -> Option<CellRef> {
+        self.cells.get(id).cloned()
+    }
+
+    /// Returns the ID of the given cell, if it's part of this grid.
+    pub fn get_cell_id(&self, cell: &CellRef) -> Option<CellID> {
+        self.cells.iter().position(|c| CellRef::ptr_eq(c, cell))
+    }
+
+    /// Returns the type of the cell with the given ID, if it exists.
+    pub fn get_cell_type(&self, id: CellID) -> Option<CellType> {
+        self.cells.get(id).map(|c| c.read().unwrap().kind)
+    }
+
+    /// Changes the type of the cell with the given ID, returning the type it had before.
+    /// Returns `None` and does nothing if the cell doesn't exist.
+    pub fn set_cell_type(&mut self, id: CellID, kind: CellType) -> Option<CellType> {
+        self.cells
+            .get(id)
+            .map(|c| std::mem::replace(&mut c.write().unwrap().kind, kind))
+    }
+
+    /// Gets the position of the top left corner of the cell with the given ID, if it exists.
+    pub fn get_cell_position(&self, id: CellID) -> Option<GridVector2> {
+        if self.cell_exists(id) {
+            Some(vec2!(id / self.size.y, id % self.size.y))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the ID of the vertex at the given position, if it exists.
+    pub fn get_vertex_id_at(&self, position: GridVector2) -> Option<VertexID> {
+        if position.x <= self.size.x && position.y <= self.size.y {
+            Some(Self::vector_to_vertex_id(self.size, position))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the ID of the cell whose top left corner is at the given position, if it exists.
+    pub fn get_cell_id_at(&self, position: GridVector2) -> Option<CellID> {
+        if position.x < self.size.x && position.y < self.size.y {
+            Some(position.x * self.size.y + position.y)
+        } else {
+            None
+        }
+    }
+
     pub fn get_vertex_position(&self, id: VertexID) -> Option<GridVector2> {
         if self.vertex_exists(id) {
             let h_vert_count = self.size.x + 1;
@@ -160,8 +274,8 @@ impl Grid {
     ) -> impl Iterator<Item = EdgeRef> + '_ {
         self.edges
             .iter()
-            .cloned()
             .filter(move |edge| edge.read().unwrap().connects_to(id))
+            .cloned()
     }
 
     /// Returns a vector containing copies of the edges connected to the given vertex.
@@ -181,13 +295,13 @@ impl Grid {
                 self.cells
                     .iter()
                     .find(|c: &&CellRef| {
-                        c.read().unwrap().has_edge(&e) && !CellRef::ptr_eq(c, &cell)
+                        c.read().unwrap().has_edge(e) && !CellRef::ptr_eq(c, &cell)
                     })
-                    .map(|x: &CellRef| x.clone())
+                    .cloned()
             })
             .collect();
         cells.sort_by(|a: &CellRef, b: &CellRef| CellRef::as_ptr(a).cmp(&CellRef::as_ptr(b)));
-        cells.dedup_by(|a: &mut CellRef, b: &mut CellRef| CellRef::ptr_eq(&a, &b));
+        cells.dedup_by(|a: &mut CellRef, b: &mut CellRef| CellRef::ptr_eq(a, b));
         cells
     }
 
@@ -201,7 +315,7 @@ impl Grid {
         self.cells
             .iter()
             .find(|c: &&CellRef| c.read().unwrap().has_edge(&edge) && !CellRef::ptr_eq(c, &cell))
-            .map(|x: &CellRef| x.clone())
+            .cloned()
     }
 
     pub fn floodfill_from_cell(&self, cell: CellRef) -> Vec<CellRef> {
@@ -212,11 +326,11 @@ impl Grid {
             let mut filtered = self
                 .get_cells_around_cell(front)
                 .into_iter()
-                .filter(|c| flood_filled.iter().any(|f| CellRef::ptr_eq(&f, c)))
+                .filter(|c| !flood_filled.iter().any(|f| CellRef::ptr_eq(f, c)))
                 .collect::<Vec<CellRef>>();
             filtered
                 .sort_by(|a: &CellRef, b: &CellRef| CellRef::as_ptr(a).cmp(&CellRef::as_ptr(b)));
-            filtered.dedup_by(|a: &mut CellRef, b: &mut CellRef| CellRef::ptr_eq(&a, &b));
+            filtered.dedup_by(|a: &mut CellRef, b: &mut CellRef| CellRef::ptr_eq(a, b));
             flood_filled.extend(filtered.clone());
             not_done.extend(filtered);
         }
@@ -224,7 +338,7 @@ impl Grid {
     }
 
     pub fn get_all_vertex_ids_iter(&self) -> impl Iterator<Item = VertexID> + '_ {
-        (0..(self.size + 1).area()).into_iter()
+        0..(self.size + 1).area()
     }
 
     pub fn get_all_edgerefs_iter(&self) -> impl Iterator<Item = EdgeRef> + '_ {
@@ -234,6 +348,44 @@ impl Grid {
     pub fn get_all_cellrefs_iter(&self) -> impl Iterator<Item = CellRef> + '_ {
         self.cells.iter().cloned()
     }
+
+    /// Takes an index-based copy of this grid that's cheap to query.
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot::new(self)
+    }
+}
+
+impl Clone for Grid {
+    /// Makes a deep copy of this grid.
+    /// The copy doesn't share any edges or cells with the original, so changing one won't affect the other.
+    fn clone(&self) -> Self {
+        let edges: Vec<EdgeRef> = self
+            .edges
+            .iter()
+            .map(|e| Rc::new(RwLock::new(*e.read().unwrap())))
+            .collect();
+        let cells = self
+            .cells
+            .iter()
+            .map(|c| {
+                let cell = c.read().unwrap();
+                let cell_edges = cell
+                    .get_edges()
+                    .iter()
+                    .map(|e| edges[self.get_edge_id(e).unwrap()].clone())
+                    .collect();
+                Rc::new(RwLock::new(
+                    Cell::new_of_kind(cell_edges, cell.kind).unwrap(),
+                ))
+            })
+            .collect();
+        Grid {
+            size: self.size,
+            vertices: self.vertices.clone(),
+            edges,
+            cells,
+        }
+    }
 }
 
 #[cfg(test)]
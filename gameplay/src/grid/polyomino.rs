@@ -0,0 +1,231 @@
+use std::collections::BTreeSet;
+
+use super::GridVector2;
+
+/// The most squares wide or tall a polyomino can be.
+pub const POLYOMINO_MAX_SIZE: usize = 4;
+
+/// The shape of a polyomino symbol.
+/// The squares of the shape are stored as a 4x4 bitmask, where bit `x + 4 * y` is set if the shape covers that square.
+/// Shapes are always moved into the top left corner, so two equal shapes always have equal masks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PolyominoData"))]
+pub struct Polyomino {
+    mask: u16,
+    rotatable: bool,
+}
+
+impl Polyomino {
+    /// Creates a polyomino covering the given squares.
+    /// Returns `None` if there are no squares, or if they don't fit in a 4x4 box.
+    pub fn new(squares: &[GridVector2], rotatable: bool) -> Option<Self> {
+        let min_x = squares.iter().map(|square| square.x).min()?;
+        let min_y = squares.iter().map(|square| square.y).min()?;
+        let mut mask = 0;
+        for square in squares {
+            let (x, y) = (square.x - min_x, square.y - min_y);
+            if x >= POLYOMINO_MAX_SIZE || y >= POLYOMINO_MAX_SIZE {
+                return None;
+            }
+            mask |= 1 << (x + POLYOMINO_MAX_SIZE * y);
+        }
+        Some(Polyomino { mask, rotatable })
+    }
+
+    /// Creates a polyomino from a bitmask as described on `Polyomino`. The shape doesn't have to be in the top left corner.
+    /// Returns `None` if the mask is empty.
+    pub fn from_mask(mask: u16, rotatable: bool) -> Option<Self> {
+        let squares: Vec<GridVector2> = (0..16)
+            .filter(|bit| mask & (1 << bit) != 0)
+            .map(|bit| vec2!(bit % POLYOMINO_MAX_SIZE, bit / POLYOMINO_MAX_SIZE))
+            .collect();
+        Self::new(&squares, rotatable)
+    }
+
+    pub fn get_mask(&self) -> u16 {
+        self.mask
+    }
+
+    /// Checks whether this polyomino may be rotated to fit into a region.
+    pub fn is_rotatable(&self) -> bool {
+        self.rotatable
+    }
+
+    /// Gets the squares this polyomino covers, row by row from the top left.
+    pub fn get_squares(&self) -> Vec<GridVector2> {
+        (0..16)
+            .filter(|bit| self.mask & (1 << bit) != 0)
+            .map(|bit| vec2!(bit % POLYOMINO_MAX_SIZE, bit / POLYOMINO_MAX_SIZE))
+            .collect()
+    }
+
+    /// Gets the amount of squares this polyomino covers.
+    pub fn get_area(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Gets the size of the smallest box this polyomino fits in.
+    pub fn get_size(&self) -> GridVector2 {
+        self.get_squares()
+            .into_iter()
+            .fold(vec2!(0, 0), |size: GridVector2, square| {
+                vec2!(size.x.max(square.x + 1), size.y.max(square.y + 1))
+            })
+    }
+
+    /// Gets this polyomino turned a quarter turn clockwise.
+    pub fn rotated(&self) -> Self {
+        let height = self.get_size().y;
+        let squares: Vec<GridVector2> = self
+            .get_squares()
+            .into_iter()
+            .map(|square| vec2!(height - 1 - square.y, square.x))
+            .collect();
+        Self::new(&squares, self.rotatable).unwrap()
+    }
+
+    /// Gets every distinct way this polyomino can be placed: all four rotations if it's rotatable, or just itself if not.
+    pub fn get_orientations(&self) -> Vec<Polyomino> {
+        let mut orientations = vec![*self];
+        if self.rotatable {
+            (0..3).for_each(|_| {
+                let next = orientations.last().unwrap().rotated();
+                if !orientations.contains(&next) {
+                    orientations.push(next);
+                }
+            });
+        }
+        orientations
+    }
+}
+
+/// How a polyomino is read when deserializing, before its mask is checked and moved into the top left corner.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PolyominoData {
+    mask: u16,
+    rotatable: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PolyominoData> for Polyomino {
+    type Error = &'static str;
+
+    fn try_from(data: PolyominoData) -> Result<Self, Self::Error> {
+        Polyomino::from_mask(data.mask, data.rotatable)
+            .ok_or("a polyomino needs at least one square")
+    }
+}
+
+/// Checks whether the given polyominoes can exactly cover a region of cells, given by their positions,
+/// without overlapping each other or sticking out of the region.
+pub fn can_tile_region(region: &[GridVector2], pieces: &[Polyomino]) -> bool {
+    if pieces.iter().map(Polyomino::get_area).sum::<usize>() != region.len() {
+        return false;
+    }
+    // Every orientation of every piece, as offsets from its first square in reading order
+    let orientations: Vec<Vec<Vec<(isize, isize)>>> = pieces
+        .iter()
+        .map(|piece| {
+            piece
+                .get_orientations()
+                .into_iter()
+                .map(|orientation| {
+                    let squares = orientation.get_squares();
+                    let first = squares[0];
+                    squares
+                        .into_iter()
+                        .map(|square| {
+                            (
+                                square.y as isize - first.y as isize,
+                                square.x as isize - first.x as isize,
+                            )
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+    // Uncovered cells are kept as (y, x) so the first one is always the top left-most
+    let mut uncovered: BTreeSet<(isize, isize)> = region
+        .iter()
+        .map(|cell| (cell.y as isize, cell.x as isize))
+        .collect();
+    let mut used = vec![false; pieces.len()];
+    fill_region(&mut uncovered, pieces, &orientations, &mut used)
+}
+
+/// Covers the top left-most uncovered cell with every piece that fits there, and recurses.
+fn fill_region(
+    uncovered: &mut BTreeSet<(isize, isize)>,
+    pieces: &[Polyomino],
+    orientations: &[Vec<Vec<(isize, isize)>>],
+    used: &mut [bool],
+) -> bool {
+    let Some(&(y, x)) = uncovered.first() else {
+        return true;
+    };
+    for i in 0..pieces.len() {
+        // Trying a piece that's identical to an earlier unused one can't give anything new
+        if used[i] || (0..i).any(|j| !used[j] && pieces[j] == pieces[i]) {
+            continue;
+        }
+        for offsets in &orientations[i] {
+            let cells: Vec<(isize, isize)> =
+                offsets.iter().map(|&(dy, dx)| (y + dy, x + dx)).collect();
+            if !cells.iter().all(|cell| uncovered.contains(cell)) {
+                continue;
+            }
+            cells.iter().for_each(|cell| _ = uncovered.remove(cell));
+            used[i] = true;
+            if fill_region(uncovered, pieces, orientations, used) {
+                return true;
+            }
+            used[i] = false;
+            uncovered.extend(cells);
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotations() {
+        // An L shape
+        let l = Polyomino::new(&[vec2!(0, 0), vec2!(0, 1), vec2!(1, 1)], true).unwrap();
+        assert_eq!(l.get_area(), 3);
+        assert_eq!(l.get_size(), vec2!(2, 2));
+        assert_eq!(l.get_orientations().len(), 4);
+        assert_eq!(l.rotated().rotated().rotated().rotated(), l);
+        // A straight line only has two distinct rotations, and fixed pieces only have one
+        let line = Polyomino::from_mask(0b1111, true).unwrap();
+        assert_eq!(line.get_orientations().len(), 2);
+        assert_eq!(line.rotated().get_size(), vec2!(1, 4));
+        let fixed = Polyomino::from_mask(0b1111 << 4, false).unwrap();
+        assert_eq!(fixed.get_mask(), 0b1111);
+        assert_eq!(fixed.get_orientations().len(), 1);
+    }
+
+    #[test]
+    fn tiling() {
+        let square: Vec<GridVector2> = (0..2)
+            .flat_map(|x| (0..2).map(move |y| vec2!(x, y)))
+            .collect();
+        let domino = Polyomino::from_mask(0b11, false).unwrap();
+        assert!(can_tile_region(&square, &[domino, domino]));
+        // Too few squares
+        assert!(!can_tile_region(&square, &[domino]));
+        // An L and a monomino fill the square only if the L can be turned to leave the right corner open
+        let l = Polyomino::new(&[vec2!(0, 0), vec2!(0, 1), vec2!(1, 1)], false).unwrap();
+        let monomino = Polyomino::from_mask(1, false).unwrap();
+        assert!(can_tile_region(&square, &[l, monomino]));
+        let l_shaped: Vec<GridVector2> = vec![vec2!(1, 0), vec2!(0, 1), vec2!(1, 1)];
+        assert!(!can_tile_region(&l_shaped, &[l]));
+        let rotatable_l = Polyomino::new(&[vec2!(0, 0), vec2!(0, 1), vec2!(1, 1)], true).unwrap();
+        assert!(can_tile_region(&l_shaped, &[rotatable_l]));
+    }
+}
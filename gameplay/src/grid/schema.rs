@@ -0,0 +1,146 @@
+//! The on-disk layout grids are serialized with when the `serde` feature is enabled.
+//!
+//! A grid is made of edges and cells that point at each other, so it isn't serialized directly.
+//! Instead it's written as its size and the kinds of its vertices, edges and cells, each listed in ID order:
+//! ```json
+//! {
+//!     "version": 1,
+//!     "size": { "x": 2, "y": 1 },
+//!     "vertices": ["Start", "Vertex", "Vertex", "Vertex", "Vertex", "Exit"],
+//!     "edges": ["Edge", "Edge", "Dot", "Edge", "Gap", "Edge", "Edge"],
+//!     "cells": ["Blank", { "Square": "Black" }]
+//! }
+//! ```
+//! The IDs follow from the size, so a grid read back has the same IDs it was written with.
+//! `version` is bumped whenever the layout changes, and grids with a version this build doesn't know are rejected.
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{CellType, EdgeType, Grid, GridVector2, Vertex};
+
+/// The version of the layout grids are currently written with.
+pub const GRID_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct GridData {
+    version: u32,
+    size: GridVector2,
+    vertices: Vec<Vertex>,
+    edges: Vec<EdgeType>,
+    cells: Vec<CellType>,
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GridData {
+            version: GRID_SCHEMA_VERSION,
+            size: self.size,
+            vertices: self.vertices.clone(),
+            edges: self
+                .edges
+                .iter()
+                .map(|e| e.read().unwrap().get_kind())
+                .collect(),
+            cells: self.cells.iter().map(|c| c.read().unwrap().kind).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = GridData::deserialize(deserializer)?;
+        if data.version != GRID_SCHEMA_VERSION {
+            return Err(D::Error::custom(format!(
+                "unsupported grid version {}, expected {}",
+                data.version, GRID_SCHEMA_VERSION
+            )));
+        }
+        // The lengths are checked before the grid is built, so a huge size can't make it build a huge grid
+        let (x, y) = (data.size.x, data.size.y);
+        let counts = (|| {
+            let vertices = x.checked_add(1)?.checked_mul(y.checked_add(1)?)?;
+            let edges = x.checked_mul(y + 1)?.checked_add(y.checked_mul(x + 1)?)?;
+            Some((vertices, edges, x.checked_mul(y)?))
+        })();
+        let Some((vertex_count, edge_count, cell_count)) = counts else {
+            return Err(D::Error::custom(format!("a {x}x{y} grid is too big")));
+        };
+        let expect_length = |what: &str, found: usize, expected: usize| {
+            if found == expected {
+                Ok(())
+            } else {
+                Err(D::Error::invalid_length(
+                    found,
+                    &format!(
+                        "{expected} {what} for a {}x{} grid",
+                        data.size.x, data.size.y
+                    )
+                    .as_str(),
+                ))
+            }
+        };
+        expect_length("vertices", data.vertices.len(), vertex_count)?;
+        expect_length("edges", data.edges.len(), edge_count)?;
+        expect_length("cells", data.cells.len(), cell_count)?;
+        let mut grid = Grid::new(data.size);
+        grid.vertices = data.vertices;
+        data.edges.into_iter().enumerate().for_each(|(id, kind)| {
+            grid.set_edge_type(id, kind);
+        });
+        data.cells.into_iter().enumerate().for_each(|(id, kind)| {
+            grid.set_cell_type(id, kind);
+        });
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::Color, path::Path};
+
+    #[test]
+    fn round_trip() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(0, Vertex::Start);
+        grid.set_vertex_type(5, Vertex::Exit);
+        grid.set_edge_type(2, EdgeType::Dot);
+        grid.set_edge_type(4, EdgeType::Gap);
+        grid.set_cell_type(1, CellType::Square(Color::Black));
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(
+            json,
+            r#"{"version":1,"size":{"x":2,"y":1},"vertices":["Start","Vertex","Vertex","Vertex","Vertex","Exit"],"edges":["Edge","Edge","Dot","Edge","Gap","Edge","Edge"],"cells":["Blank",{"Square":"Black"}]}"#
+        );
+        let read: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+        assert_eq!(read.get_cell_type(1), Some(CellType::Square(Color::Black)));
+
+        let path = Path::from_vertices(vec![0, 1, 4, 5]);
+        assert_eq!(serde_json::to_string(&path).unwrap(), "[0,1,4,5]");
+    }
+
+    #[test]
+    fn rejects_bad_grids() {
+        let newer = r#"{"version":2,"size":{"x":1,"y":1},"vertices":[],"edges":[],"cells":[]}"#;
+        assert!(serde_json::from_str::<Grid>(newer)
+            .unwrap_err()
+            .to_string()
+            .contains("unsupported grid version 2"));
+        let short =
+            r#"{"version":1,"size":{"x":1,"y":1},"vertices":["Vertex"],"edges":[],"cells":[]}"#;
+        assert!(serde_json::from_str::<Grid>(short).is_err());
+        // Sizes that don't match the lists are rejected before a grid is built for them
+        let huge =
+            r#"{"version":1,"size":{"x":200000,"y":200000},"vertices":[],"edges":[],"cells":[]}"#;
+        assert!(serde_json::from_str::<Grid>(huge).is_err());
+        let overflowing = r#"{"version":1,"size":{"x":18446744073709551615,"y":1},"vertices":[],"edges":[],"cells":[]}"#;
+        assert!(serde_json::from_str::<Grid>(overflowing)
+            .unwrap_err()
+            .to_string()
+            .contains("too big"));
+        let empty_polyomino = r#"{"Polyomino":{"mask":0,"rotatable":false}}"#;
+        assert!(serde_json::from_str::<CellType>(empty_polyomino).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use super::{CellID, CellType, Edge, EdgeID, EdgeType, Grid, GridVector2, Vertex, VertexID};
+
+/// A read-only copy of a `Grid` that refers to everything by ID instead of by reference.
+/// Looking things up in a snapshot doesn't need any locking or pointer comparisons,
+/// which makes it a lot cheaper for code that needs to query the grid many times, like the solver.
+#[derive(Debug, Clone)]
+pub struct GridSnapshot {
+    size: GridVector2,
+    vertices: Vec<Vertex>,
+    vertex_positions: Vec<GridVector2>,
+    edges: Vec<Edge>,
+    cells: Vec<CellType>,
+    cell_positions: Vec<GridVector2>,
+    cell_edges: Vec<Vec<EdgeID>>,
+    vertex_edges: Vec<Vec<EdgeID>>,
+    edge_cells: Vec<Vec<CellID>>,
+}
+
+impl GridSnapshot {
+    /// Takes a snapshot of the given grid.
+    pub fn new(grid: &Grid) -> Self {
+        let vertices: Vec<Vertex> = grid
+            .get_all_vertex_ids_iter()
+            .map(|v| grid.get_vertex_type(v).unwrap())
+            .collect();
+        let edges: Vec<Edge> = grid
+            .get_all_edgerefs_iter()
+            .map(|e| *e.read().unwrap())
+            .collect();
+        let mut vertex_edges = vec![vec![]; vertices.len()];
+        edges.iter().enumerate().for_each(|(id, edge)| {
+            edge.get_vertices_array()
+                .into_iter()
+                .for_each(|v| vertex_edges[v].push(id))
+        });
+        let mut edge_cells = vec![vec![]; edges.len()];
+        let mut cells = vec![];
+        let mut cell_edges = vec![];
+        grid.get_all_cellrefs_iter()
+            .enumerate()
+            .for_each(|(id, c)| {
+                let cell = c.read().unwrap();
+                let ids: Vec<EdgeID> = cell
+                    .get_edges()
+                    .iter()
+                    .map(|e| grid.get_edge_id(e).unwrap())
+                    .collect();
+                ids.iter().for_each(|&e| edge_cells[e].push(id));
+                cells.push(cell.kind);
+                cell_edges.push(ids);
+            });
+        GridSnapshot {
+            size: grid.get_size(),
+            vertex_positions: grid
+                .get_all_vertex_ids_iter()
+                .map(|v| grid.get_vertex_position(v).unwrap())
+                .collect(),
+            vertices,
+            edges,
+            cell_positions: (0..cells.len())
+                .map(|c| grid.get_cell_position(c).unwrap())
+                .collect(),
+            cells,
+            cell_edges,
+            vertex_edges,
+            edge_cells,
+        }
+    }
+
+    /// Gets the size of the grid this snapshot was taken of, measured in square grid cells.
+    pub fn get_size(&self) -> GridVector2 {
+        self.size
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the type of the given vertex, if it exists.
+    pub fn get_vertex_type(&self, id: VertexID) -> Option<Vertex> {
+        self.vertices.get(id).copied()
+    }
+
+    /// Returns the position of the given vertex, if it exists.
+    pub fn get_vertex_position(&self, id: VertexID) -> Option<GridVector2> {
+        self.vertex_positions.get(id).copied()
+    }
+
+    /// Returns the position of the top left corner of the given cell, if it exists.
+    pub fn get_cell_position(&self, id: CellID) -> Option<GridVector2> {
+        self.cell_positions.get(id).copied()
+    }
+
+    /// Returns a copy of the given edge, if it exists.
+    pub fn get_edge(&self, id: EdgeID) -> Option<Edge> {
+        self.edges.get(id).copied()
+    }
+
+    /// Returns the type of the given edge, if it exists.
+    pub fn get_edge_type(&self, id: EdgeID) -> Option<EdgeType> {
+        self.edges.get(id).map(Edge::get_kind)
+    }
+
+    /// Returns the type of the given cell, if it exists.
+    pub fn get_cell_type(&self, id: CellID) -> Option<CellType> {
+        self.cells.get(id).copied()
+    }
+
+    /// Returns the IDs of the edges that border the given cell, in winding order.
+    pub fn get_edges_of_cell(&self, id: CellID) -> &[EdgeID] {
+        &self.cell_edges[id]
+    }
+
+    /// Returns the IDs of the edges connected to the given vertex.
+    pub fn get_edges_of_vertex(&self, id: VertexID) -> &[EdgeID] {
+        &self.vertex_edges[id]
+    }
+
+    /// Returns the IDs of the cells on either side of the given edge.
+    /// Edges on the outside of the grid only have one cell.
+    pub fn get_cells_of_edge(&self, id: EdgeID) -> &[CellID] {
+        &self.edge_cells[id]
+    }
+
+    /// Returns the ID of the edge connecting two vertices, if there is one.
+    pub fn get_edge_between(&self, a: VertexID, b: VertexID) -> Option<EdgeID> {
+        self.vertex_edges
+            .get(a)?
+            .iter()
+            .copied()
+            .find(|&e| self.edges[e].connects_to(b))
+    }
+
+    /// Returns an iterator over the vertices that can be reached from the given vertex in a single step,
+    /// together with the edge that has to be crossed to get there.
+    /// Gaps and vertices that don't exist on the grid are skipped.
+    pub fn get_neighbours_iter(
+        &self,
+        id: VertexID,
+    ) -> impl Iterator<Item = (EdgeID, VertexID)> + '_ {
+        self.vertex_edges[id].iter().filter_map(move |&e| {
+            let edge = &self.edges[e];
+            let other = edge.get_other_vertex(id)?;
+            (edge.get_kind() != EdgeType::Gap && self.vertices[other] != Vertex::None)
+                .then_some((e, other))
+        })
+    }
+
+    pub fn get_all_vertex_ids_iter(&self) -> impl Iterator<Item = VertexID> {
+        0..self.vertices.len()
+    }
+
+    pub fn get_all_edge_ids_iter(&self) -> impl Iterator<Item = EdgeID> {
+        0..self.edges.len()
+    }
+
+    pub fn get_all_cell_ids_iter(&self) -> impl Iterator<Item = CellID> {
+        0..self.cells.len()
+    }
+
+    /// Splits the grid into regions, given which edges the line is drawn over.
+    /// `used_edges` is indexed by edge ID. Every cell ends up in exactly one region.
+    pub fn get_regions(&self, used_edges: &[bool]) -> Vec<Vec<CellID>> {
+        let mut region_of_cell: Vec<Option<usize>> = vec![None; self.cells.len()];
+        let mut regions = vec![];
+        for start in self.get_all_cell_ids_iter() {
+            if region_of_cell[start].is_some() {
+                continue;
+            }
+            let mut region = vec![start];
+            region_of_cell[start] = Some(regions.len());
+            let mut index = 0;
+            while let Some(&cell) = region.get(index) {
+                index += 1;
+                for &edge in &self.cell_edges[cell] {
+                    if used_edges[edge] {
+                        continue;
+                    }
+                    for &other in &self.edge_cells[edge] {
+                        if region_of_cell[other].is_none() {
+                            region_of_cell[other] = Some(regions.len());
+                            region.push(other);
+                        }
+                    }
+                }
+            }
+            regions.push(region);
+        }
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_matches_grid() {
+        let grid = Grid::new(vec2!(3, 2));
+        let snapshot = grid.snapshot();
+        assert_eq!(snapshot.vertex_count(), 12);
+        assert_eq!(snapshot.edge_count(), 17);
+        assert_eq!(snapshot.cell_count(), 6);
+        snapshot.get_all_cell_ids_iter().for_each(|c| {
+            assert_eq!(snapshot.get_edges_of_cell(c).len(), 4);
+        });
+    }
+
+    #[test]
+    fn regions_split_by_used_edges() {
+        let grid = Grid::new(vec2!(2, 1));
+        let snapshot = grid.snapshot();
+        let mut used = vec![false; snapshot.edge_count()];
+        assert_eq!(snapshot.get_regions(&used).len(), 1);
+        let middle = snapshot.get_edge_between(1, 4).unwrap();
+        used[middle] = true;
+        assert_eq!(snapshot.get_regions(&used).len(), 2);
+    }
+}
@@ -1,7 +1,8 @@
 /// The identifier for a vertex.
 pub type VertexID = usize;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Vertex {
     /// This vertex does not exist on the grid.
     None,
@@ -9,4 +10,8 @@ pub enum Vertex {
     Vertex,
     /// This is a vertex with a dot on it, meaning the solution requires the line drawing over it.
     Dot,
+    /// This is a vertex the line can be started from.
+    Start,
+    /// This is a vertex with an exit nub attached to it, where the line can end.
+    Exit,
 }
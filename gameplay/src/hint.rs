@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+
+use crate::{
+    deduction::{DeductionOutcome, DeductionSolver},
+    grid::{CellID, EdgeID, Grid, GridSnapshot, VertexID},
+    path::Path,
+    solver::Solver,
+    validation::{FailingSymbol, PathError},
+};
+
+/// Something to tell a player who's stuck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// Nothing has been drawn yet; the line should begin at this start.
+    StartAt(VertexID),
+    /// The line can be finished, and crossing this edge to this vertex is the next step towards a solution.
+    NextEdge { edge: EdgeID, to: VertexID },
+    /// The region made up of these cells is closed off and some of its symbols can no longer be satisfied.
+    WrongRegion {
+        cells: Vec<CellID>,
+        symbols: Vec<FailingSymbol>,
+    },
+    /// No matter how the line continues, it won't solve the puzzle.
+    Doomed(DoomReason),
+    /// The line already solves the puzzle.
+    Solved,
+}
+
+/// Why a partial line can't be finished into a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DoomReason {
+    /// The puzzle doesn't have any solutions, whatever the player draws.
+    Unsolvable,
+    /// The line itself can't be drawn like this.
+    InvalidPath(PathError),
+    /// Deduction found a reason the line can't work, explained in words.
+    Contradiction(String),
+    /// The solver found no way to finish the line, but there's no simple explanation why.
+    NoWayToFinish,
+}
+
+/// Gives hints for a single puzzle.
+/// This keeps a snapshot of the grid around, so it's cheap to ask for hints every time the line changes.
+#[derive(Debug, Clone)]
+pub struct Hinter {
+    solver: Solver,
+}
+
+impl Hinter {
+    pub fn new(grid: &Grid) -> Self {
+        Hinter {
+            solver: Solver::new(grid),
+        }
+    }
+
+    pub fn get_snapshot(&self) -> &GridSnapshot {
+        self.solver.get_snapshot()
+    }
+
+    /// Gets a hint for the given partial line.
+    /// A closed off region that's wrong is reported before anything else, since it's the most specific hint;
+    /// otherwise the solver decides whether the line can still be finished.
+    pub fn get_hint(&self, path: &Path) -> Hint {
+        let snapshot = self.get_snapshot();
+        if path.is_empty() {
+            return match self.solver.solve(Some(1)).solutions.first() {
+                Some(solution) => Hint::StartAt(solution.first().unwrap()),
+                None => Hint::Doomed(DoomReason::Unsolvable),
+            };
+        }
+        if let Err(error) = snapshot.check_path(path, false) {
+            return Hint::Doomed(DoomReason::InvalidPath(error));
+        }
+        if let Some(hint) = self.find_wrong_region(path) {
+            return hint;
+        }
+        match self.solver.solve_from(path, Some(1)).solutions.first() {
+            Some(solution) if solution == path => Hint::Solved,
+            Some(solution) => {
+                let from = path.last().unwrap();
+                let to = solution.get_vertices()[path.len()];
+                Hint::NextEdge {
+                    edge: snapshot.get_edge_between(from, to).unwrap(),
+                    to,
+                }
+            }
+            None if self.solver.count_solutions(1) == 0 => Hint::Doomed(DoomReason::Unsolvable),
+            None => Hint::Doomed(self.explain_doom(path)),
+        }
+    }
+
+    /// Looks for a region the line has closed off for good whose symbols aren't satisfied.
+    /// A region is closed off once the line can't reach any of the edges around its cells anymore.
+    fn find_wrong_region(&self, path: &Path) -> Option<Hint> {
+        let snapshot = self.get_snapshot();
+        let mut visited = vec![false; snapshot.vertex_count()];
+        path.get_vertices().iter().for_each(|&v| visited[v] = true);
+        let used_edges = path.get_used_edges(snapshot);
+
+        // Every vertex the line could still get to from where it ends
+        let mut reachable = vec![false; snapshot.vertex_count()];
+        let end = path.last().unwrap();
+        let mut queue = VecDeque::from([end]);
+        reachable[end] = true;
+        while let Some(vertex) = queue.pop_front() {
+            snapshot.get_neighbours_iter(vertex).for_each(|(_, next)| {
+                if !visited[next] && !reachable[next] {
+                    reachable[next] = true;
+                    queue.push_back(next);
+                }
+            });
+        }
+        let can_still_change = |cell: &CellID| {
+            snapshot.get_edges_of_cell(*cell).iter().any(|&e| {
+                !used_edges[e]
+                    && snapshot
+                        .get_edge(e)
+                        .unwrap()
+                        .get_vertices_array()
+                        .iter()
+                        .all(|&v| reachable[v])
+            })
+        };
+
+        let failing = snapshot.get_failing_symbols(&visited, &used_edges);
+        snapshot
+            .get_regions(&used_edges)
+            .into_iter()
+            .filter(|region| !region.iter().any(can_still_change))
+            .find_map(|region| {
+                let symbols: Vec<FailingSymbol> = failing
+                    .iter()
+                    .copied()
+                    .filter(|symbol| matches!(symbol, FailingSymbol::Cell(c) if region.contains(c)))
+                    .collect();
+                (!symbols.is_empty()).then_some(Hint::WrongRegion {
+                    cells: region,
+                    symbols,
+                })
+            })
+    }
+
+    /// Tries to explain why a line can't be finished, using the deduction solver.
+    fn explain_doom(&self, path: &Path) -> DoomReason {
+        let mut deduction = DeductionSolver::from_snapshot(self.get_snapshot().clone());
+        deduction.assume_path(path);
+        match deduction.solve().outcome {
+            DeductionOutcome::Contradiction(explanation) => DoomReason::Contradiction(explanation),
+            _ => DoomReason::NoWayToFinish,
+        }
+    }
+}
+
+impl Grid {
+    /// Gets a hint for the given partial line on this grid.
+    /// To ask for hints repeatedly, create a `Hinter` once instead.
+    pub fn get_hint(&self, path: &Path) -> Hint {
+        Hinter::new(self).get_hint(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{CellType, Color, EdgeType, Vertex};
+
+    fn squares_grid() -> Grid {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        grid
+    }
+
+    #[test]
+    fn hint_next_edge() {
+        let grid = squares_grid();
+        let hinter = Hinter::new(&grid);
+        assert_eq!(hinter.get_hint(&Path::new()), Hint::StartAt(3));
+        assert_eq!(
+            hinter.get_hint(&Path::from_vertices(vec![3, 0])),
+            Hint::NextEdge {
+                edge: grid.get_edge_id_between(0, 1).unwrap(),
+                to: 1
+            }
+        );
+        assert_eq!(
+            hinter.get_hint(&Path::from_vertices(vec![3, 4, 1, 2])),
+            Hint::Solved
+        );
+    }
+
+    #[test]
+    fn hint_doomed() {
+        let grid = squares_grid();
+        assert_eq!(
+            grid.get_hint(&Path::from_vertices(vec![3, 1])),
+            Hint::Doomed(DoomReason::InvalidPath(PathError::NotConnected(3, 1)))
+        );
+        // Going right along the bottom walls off the only edge that separates the squares
+        assert!(matches!(
+            grid.get_hint(&Path::from_vertices(vec![3, 4, 5])),
+            Hint::Doomed(DoomReason::Contradiction(_))
+        ));
+
+        let mut unsolvable = grid.clone();
+        unsolvable.set_edge_type(grid.get_edge_id_between(1, 4).unwrap(), EdgeType::Gap);
+        assert_eq!(
+            unsolvable.get_hint(&Path::from_vertices(vec![3, 0])),
+            Hint::Doomed(DoomReason::Unsolvable)
+        );
+    }
+
+    #[test]
+    fn hint_wrong_region() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(0, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Star(Color::Black));
+        grid.set_cell_type(1, CellType::Star(Color::Black));
+        // Going around the left cell cuts the two stars apart, and neither side can change anymore
+        let hint = grid.get_hint(&Path::from_vertices(vec![3, 4, 1, 0]));
+        let Hint::WrongRegion { cells, symbols } = hint else {
+            panic!("expected a wrong region, got {hint:?}");
+        };
+        assert_eq!(cells.len(), 1);
+        assert_eq!(symbols, vec![FailingSymbol::Cell(cells[0])]);
+        assert_eq!(
+            grid.get_hint(&Path::from_vertices(vec![3, 0])),
+            Hint::Solved
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::{CellID, CellType, EdgeID, EdgeType, Grid, Vertex, VertexID},
+    path::Path,
+};
+
+/// How many commands a history remembers by default.
+pub const DEFAULT_HISTORY_CAPACITY: usize = 256;
+
+/// A change that can be done and undone on some target.
+pub trait Command {
+    type Target;
+
+    /// Makes the change.
+    fn apply(&self, target: &mut Self::Target);
+
+    /// Takes the change back. This is only called right after the change was applied,
+    /// or after the changes recorded after it were all reverted.
+    fn revert(&self, target: &mut Self::Target);
+}
+
+/// A single step of drawing a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineStep {
+    /// The line was extended to this vertex.
+    Extend(VertexID),
+    /// The line was backed up, taking this vertex off its end.
+    Retract(VertexID),
+}
+
+impl LineStep {
+    /// Gets the steps that turn one line into another: backing up to where they split, then extending.
+    pub fn get_steps_between(from: &Path, to: &Path) -> Vec<LineStep> {
+        let shared = from
+            .get_vertices()
+            .iter()
+            .zip(to.get_vertices())
+            .take_while(|(a, b)| a == b)
+            .count();
+        from.get_vertices()[shared..]
+            .iter()
+            .rev()
+            .map(|&v| LineStep::Retract(v))
+            .chain(
+                to.get_vertices()[shared..]
+                    .iter()
+                    .map(|&v| LineStep::Extend(v)),
+            )
+            .collect()
+    }
+}
+
+impl Command for LineStep {
+    type Target = Path;
+
+    fn apply(&self, path: &mut Path) {
+        match *self {
+            LineStep::Extend(vertex) => path.push(vertex),
+            LineStep::Retract(_) => _ = path.pop(),
+        }
+    }
+
+    fn revert(&self, path: &mut Path) {
+        match *self {
+            LineStep::Extend(_) => _ = path.pop(),
+            LineStep::Retract(vertex) => path.push(vertex),
+        }
+    }
+}
+
+/// A change to the kind of a vertex, edge or cell, as made through the grid's authoring API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridEdit {
+    Vertex {
+        id: VertexID,
+        before: Vertex,
+        after: Vertex,
+    },
+    Edge {
+        id: EdgeID,
+        before: EdgeType,
+        after: EdgeType,
+    },
+    Cell {
+        id: CellID,
+        before: CellType,
+        after: CellType,
+    },
+}
+
+impl GridEdit {
+    /// Sets the kind of a vertex and returns the edit that did it, or `None` if the vertex doesn't exist.
+    pub fn set_vertex_type(grid: &mut Grid, id: VertexID, kind: Vertex) -> Option<GridEdit> {
+        grid.set_vertex_type(id, kind)
+            .map(|before| GridEdit::Vertex {
+                id,
+                before,
+                after: kind,
+            })
+    }
+
+    /// Sets the kind of an edge and returns the edit that did it, or `None` if the edge doesn't exist.
+    pub fn set_edge_type(grid: &mut Grid, id: EdgeID, kind: EdgeType) -> Option<GridEdit> {
+        grid.set_edge_type(id, kind).map(|before| GridEdit::Edge {
+            id,
+            before,
+            after: kind,
+        })
+    }
+
+    /// Sets the kind of a cell and returns the edit that did it, or `None` if the cell doesn't exist.
+    pub fn set_cell_type(grid: &mut Grid, id: CellID, kind: CellType) -> Option<GridEdit> {
+        grid.set_cell_type(id, kind).map(|before| GridEdit::Cell {
+            id,
+            before,
+            after: kind,
+        })
+    }
+
+    /// Checks whether this edit didn't actually change anything.
+    pub fn is_noop(&self) -> bool {
+        match self {
+            GridEdit::Vertex { before, after, .. } => before == after,
+            GridEdit::Edge { before, after, .. } => before == after,
+            GridEdit::Cell { before, after, .. } => before == after,
+        }
+    }
+}
+
+impl Command for GridEdit {
+    type Target = Grid;
+
+    fn apply(&self, grid: &mut Grid) {
+        match *self {
+            GridEdit::Vertex { id, after, .. } => _ = grid.set_vertex_type(id, after),
+            GridEdit::Edge { id, after, .. } => _ = grid.set_edge_type(id, after),
+            GridEdit::Cell { id, after, .. } => _ = grid.set_cell_type(id, after),
+        }
+    }
+
+    fn revert(&self, grid: &mut Grid) {
+        match *self {
+            GridEdit::Vertex { id, before, .. } => _ = grid.set_vertex_type(id, before),
+            GridEdit::Edge { id, before, .. } => _ = grid.set_edge_type(id, before),
+            GridEdit::Cell { id, before, .. } => _ = grid.set_cell_type(id, before),
+        }
+    }
+}
+
+/// Remembers changes so they can be undone and redone.
+/// Once more than `capacity` changes have been recorded, the oldest ones are forgotten.
+#[derive(Debug, Clone)]
+pub struct History<C: Command> {
+    done: VecDeque<C>,
+    undone: Vec<C>,
+    capacity: usize,
+}
+
+impl<C: Command> History<C> {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            done: VecDeque::new(),
+            undone: vec![],
+            capacity,
+        }
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Records a change that has already been made. This forgets everything that could be redone.
+    pub fn record(&mut self, command: C) {
+        self.undone.clear();
+        if self.capacity == 0 {
+            return;
+        }
+        if self.done.len() == self.capacity {
+            self.done.pop_front();
+        }
+        self.done.push_back(command);
+    }
+
+    /// Makes a change and records it.
+    pub fn execute(&mut self, command: C, target: &mut C::Target) {
+        command.apply(target);
+        self.record(command);
+    }
+
+    /// Takes back the most recent change. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, target: &mut C::Target) -> bool {
+        let Some(command) = self.done.pop_back() else {
+            return false;
+        };
+        command.revert(target);
+        self.undone.push(command);
+        true
+    }
+
+    /// Makes the most recently undone change again. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, target: &mut C::Target) -> bool {
+        let Some(command) = self.undone.pop() else {
+            return false;
+        };
+        command.apply(target);
+        self.done.push_back(command);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Forgets every recorded change.
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+impl<C: Command> Default for History<C> {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Color;
+
+    #[test]
+    fn undo_and_redo_edits() {
+        let mut grid = Grid::new(vec2!(2, 2));
+        let mut history = History::default();
+        let square = CellType::Square(Color::Black);
+        history.record(GridEdit::set_cell_type(&mut grid, 0, square).unwrap());
+        history.record(GridEdit::set_vertex_type(&mut grid, 4, Vertex::Dot).unwrap());
+        assert!(GridEdit::set_edge_type(&mut grid, 1000, EdgeType::Gap).is_none());
+
+        assert!(history.undo(&mut grid));
+        assert_eq!(grid.get_vertex_type(4), Some(Vertex::Vertex));
+        assert!(history.undo(&mut grid));
+        assert_eq!(grid.get_cell_type(0), Some(CellType::Blank));
+        assert!(!history.undo(&mut grid));
+
+        assert!(history.redo(&mut grid));
+        assert_eq!(grid.get_cell_type(0), Some(square));
+        // Recording something new throws away what could have been redone
+        history.execute(
+            GridEdit::Cell {
+                id: 0,
+                before: square,
+                after: CellType::Blank,
+            },
+            &mut grid,
+        );
+        assert!(!history.can_redo());
+        assert_eq!(grid.get_cell_type(0), Some(CellType::Blank));
+    }
+
+    #[test]
+    fn line_steps_are_bounded() {
+        let mut path = Path::starting_at(3);
+        let mut history = History::new(2);
+        [4, 1, 2].into_iter().for_each(|vertex| {
+            history.execute(LineStep::Extend(vertex), &mut path);
+        });
+        assert!(history.undo(&mut path));
+        assert!(history.undo(&mut path));
+        // The first step was forgotten
+        assert!(!history.undo(&mut path));
+        assert_eq!(path.get_vertices(), &vec![3, 4]);
+    }
+
+    #[test]
+    fn steps_between_lines() {
+        let from = Path::from_vertices(vec![3, 4, 5]);
+        let to = Path::from_vertices(vec![3, 4, 1, 2]);
+        let steps = LineStep::get_steps_between(&from, &to);
+        assert_eq!(
+            steps,
+            vec![
+                LineStep::Retract(5),
+                LineStep::Extend(1),
+                LineStep::Extend(2)
+            ]
+        );
+        let mut path = from.clone();
+        steps.iter().for_each(|step| step.apply(&mut path));
+        assert_eq!(path, to);
+        steps.iter().rev().for_each(|step| step.revert(&mut path));
+        assert_eq!(path, from);
+    }
+}
@@ -0,0 +1,225 @@
+//! Drawing the line with directions and buttons instead of a cursor: arrow keys, d-pads and analog sticks.
+//! Frontends map their own keys and buttons to `LineInput`s and stick positions, and a `LineController`
+//! turns them into moves of a session's line, so every frontend controls the line the same way.
+
+use crate::{
+    grid::{GridPoint, Vertex, VertexID},
+    session::{PuzzleSession, SessionState},
+};
+
+/// How far a stick has to be pushed before it moves the line, from 0 to 1.
+pub const STICK_DEAD_ZONE: f32 = 0.25;
+/// How fast a fully pushed stick moves the line, in grid units per second.
+pub const STICK_SPEED: f32 = 3.;
+/// How close to a vertex the head counts as being on it, so stepping moves on to the next one.
+const STEP_EPSILON: f32 = 1e-3;
+
+/// A direction on a d-pad or the arrow keys. Up is towards smaller y, like in grid space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Gets a movement of one grid unit in this direction.
+    pub fn to_vector(self) -> GridPoint {
+        match self {
+            Direction::Up => vec2!(0., -1.),
+            Direction::Down => vec2!(0., 1.),
+            Direction::Left => vec2!(-1., 0.),
+            Direction::Right => vec2!(1., 0.),
+        }
+    }
+}
+
+/// A button press that controls the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineInput {
+    /// Moves the line on to the next vertex in a direction.
+    /// Before a line is started, this picks the start in that direction instead.
+    Step(Direction),
+    /// Starts a line at the picked start, or lets go of the line being drawn, submitting it if it's at an exit.
+    Confirm,
+    /// Throws away the line being drawn.
+    Cancel,
+}
+
+/// Turns button presses and stick movement into moves of a session's line.
+#[derive(Debug, Clone, Default)]
+pub struct LineController {
+    /// The start a line is started at, if one was picked. The first start is used otherwise.
+    selected_start: Option<VertexID>,
+    /// How far the stick is pushed, from -1 to 1 on both axes, with y pointing down.
+    stick: GridPoint,
+}
+
+/// Gets the distance from a coordinate to the next whole one in a direction, or 1 if it's already on one.
+fn get_distance_to_next(coordinate: f32, direction: f32) -> f32 {
+    let next = if direction > 0. {
+        coordinate.ceil() - coordinate
+    } else {
+        coordinate - coordinate.floor()
+    };
+    if next < STEP_EPSILON {
+        1.
+    } else {
+        next
+    }
+}
+
+impl LineController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the start a line would be started at, if the puzzle has any.
+    pub fn get_selected_start(&self, session: &PuzzleSession) -> Option<VertexID> {
+        let is_start = |vertex| session.get_grid().get_vertex_type(vertex) == Some(Vertex::Start);
+        self.selected_start
+            .filter(|&vertex| is_start(vertex))
+            .or_else(|| {
+                session
+                    .get_grid()
+                    .get_all_vertex_ids_iter()
+                    .find(|&vertex| is_start(vertex))
+            })
+    }
+
+    /// Gets where the start a line would be started at is, in grid space.
+    pub fn get_selected_start_point(&self, session: &PuzzleSession) -> Option<GridPoint> {
+        let start = self.get_selected_start(session)?;
+        let position = session.get_grid().get_vertex_position(start)?;
+        Some(vec2cast!(position, f32))
+    }
+
+    /// Picks the closest start in a direction from the one that's picked now, if there is one.
+    fn select_start_towards(&mut self, session: &PuzzleSession, direction: Direction) {
+        let Some(from) = self.get_selected_start_point(session) else {
+            return;
+        };
+        let grid = session.get_grid();
+        self.selected_start = grid
+            .get_all_vertex_ids_iter()
+            .filter(|&vertex| grid.get_vertex_type(vertex) == Some(Vertex::Start))
+            .map(|vertex| {
+                let offset = vec2cast!(grid.get_vertex_position(vertex).unwrap(), f32) - from;
+                (vertex, offset)
+            })
+            .filter(|&(_, offset)| offset.dot(direction.to_vector()) > 0.)
+            .min_by(|a, b| a.1.mag().total_cmp(&b.1.mag()))
+            .map(|(vertex, _)| vertex)
+            .or(self.selected_start);
+    }
+
+    /// Reacts to a button press.
+    pub fn handle_input(&mut self, session: &mut PuzzleSession, input: LineInput) {
+        let drawing = session.get_state() == SessionState::Drawing;
+        match input {
+            LineInput::Step(direction) if drawing => {
+                let Some(head) = session.get_tracer().get_head_point() else {
+                    return;
+                };
+                let vector = direction.to_vector();
+                let distance = if vector.x != 0. {
+                    get_distance_to_next(head.x, vector.x)
+                } else {
+                    get_distance_to_next(head.y, vector.y)
+                };
+                session.move_by(vector * distance);
+            }
+            LineInput::Step(direction) => self.select_start_towards(session, direction),
+            LineInput::Confirm if drawing => session.release(),
+            LineInput::Confirm => {
+                if let Some(start) = self.get_selected_start_point(session) {
+                    session.start_near(start, 0.);
+                }
+            }
+            LineInput::Cancel => session.abandon(),
+        }
+    }
+
+    /// Sets how far the stick is pushed, from -1 to 1 on both axes, with y pointing down.
+    pub fn set_stick(&mut self, stick: GridPoint) {
+        self.stick = stick;
+    }
+
+    /// Moves the line along with the stick for the given amount of seconds. Call this every frame.
+    pub fn update(&mut self, session: &mut PuzzleSession, delta_time: f32) {
+        let push = self.stick.mag().min(1.);
+        if push <= STICK_DEAD_ZONE {
+            return;
+        }
+        // Speed up smoothly from the edge of the dead zone, rather than jumping to its speed
+        let speed = (push - STICK_DEAD_ZONE) / (1. - STICK_DEAD_ZONE) * STICK_SPEED;
+        session.move_by(self.stick.norm() * (speed * delta_time));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn session() -> PuzzleSession {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(5, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        PuzzleSession::new(grid)
+    }
+
+    #[test]
+    fn steps_to_an_exit() {
+        let mut session = session();
+        let mut controller = LineController::new();
+        assert_eq!(controller.get_selected_start(&session), Some(3));
+        controller.handle_input(&mut session, LineInput::Confirm);
+        assert_eq!(session.get_state(), SessionState::Drawing);
+        for direction in [
+            Direction::Up,
+            Direction::Right,
+            Direction::Right,
+            Direction::Right,
+        ] {
+            controller.handle_input(&mut session, LineInput::Step(direction));
+        }
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 0, 1, 2]);
+        // The last step went into the exit's nub
+        controller.handle_input(&mut session, LineInput::Confirm);
+        assert_eq!(session.get_state(), SessionState::Submitted);
+    }
+
+    #[test]
+    fn picks_starts_and_cancels() {
+        let mut session = session();
+        let mut controller = LineController::new();
+        controller.handle_input(&mut session, LineInput::Step(Direction::Left));
+        assert_eq!(controller.get_selected_start(&session), Some(3));
+        controller.handle_input(&mut session, LineInput::Step(Direction::Right));
+        assert_eq!(controller.get_selected_start(&session), Some(5));
+        controller.handle_input(&mut session, LineInput::Confirm);
+        assert_eq!(session.get_path().get_vertices(), &vec![5]);
+        controller.handle_input(&mut session, LineInput::Cancel);
+        assert_eq!(session.get_state(), SessionState::Idle);
+    }
+
+    #[test]
+    fn follows_the_stick() {
+        let mut session = session();
+        let mut controller = LineController::new();
+        controller.handle_input(&mut session, LineInput::Confirm);
+        // A stick resting in the dead zone doesn't move the line
+        controller.set_stick(vec2!(0.1, 0.));
+        controller.update(&mut session, 1.);
+        assert_eq!(session.get_line(), vec![vec2!(0., 1.)]);
+        controller.set_stick(vec2!(1., 0.));
+        controller.update(&mut session, 0.5 / STICK_SPEED);
+        assert_eq!(session.get_line(), vec![vec2!(0., 1.), vec2!(0.5, 1.)]);
+        // Stepping from partway along an edge finishes the edge
+        controller.handle_input(&mut session, LineInput::Step(Direction::Right));
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 4]);
+    }
+}
@@ -1,7 +1,29 @@
 #[macro_use]
 extern crate witness_core;
 
+pub mod analysis;
+pub mod constraint;
+pub mod deduction;
+pub mod difficulty;
+pub mod editor;
+pub mod event;
+pub mod generator;
 pub mod grid;
+pub mod hint;
+pub mod history;
+pub mod input;
+pub mod minimizer;
+pub mod pack;
+pub mod path;
+pub mod random;
+pub mod session;
+pub mod solver;
+pub mod text;
+pub mod tracer;
+pub mod validation;
+pub mod windmill;
+#[cfg(feature = "witnesspuzzles")]
+pub mod witnesspuzzles;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
@@ -0,0 +1,130 @@
+use crate::{constraint::Constraint, grid::Grid};
+
+/// The outcome of minimizing a puzzle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinimizeReport {
+    /// The constraints that were taken off the grid because the original solution stayed the only one without them.
+    pub removed: Vec<Constraint>,
+    /// The constraints left on the grid. Taking away any one of these would give the puzzle more solutions,
+    /// or a different one.
+    pub kept: Vec<Constraint>,
+}
+
+/// Describes why a puzzle couldn't be minimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimizeError {
+    /// The puzzle doesn't have exactly one solution to begin with.
+    /// This holds the amount of solutions that were found, counting up to 2.
+    NotUnique(usize),
+}
+
+/// Greedily removes constraints from a uniquely solvable puzzle for as long as its solution stays the only one.
+/// A removal that leaves a single solution that's a different path isn't kept, as the solution would change.
+/// Constraints are tried in the order `Constraint::get_all` lists them.
+/// The result is minimal in the sense that no single remaining constraint can be removed,
+/// but a different removal order could end up with fewer constraints.
+pub fn minimize(grid: &mut Grid) -> Result<MinimizeReport, MinimizeError> {
+    minimize_in_order(grid, Constraint::get_all(grid))
+}
+
+/// Like `minimize`, but tries removing the given constraints in the given order.
+/// Constraints that aren't in the list are always kept and aren't reported.
+pub fn minimize_in_order(
+    grid: &mut Grid,
+    constraints: Vec<Constraint>,
+) -> Result<MinimizeReport, MinimizeError> {
+    let solutions = vec![match grid.solve(Some(2)).solutions.as_slice() {
+        [solution] => solution.clone(),
+        solutions => return Err(MinimizeError::NotUnique(solutions.len())),
+    }];
+    let mut report = MinimizeReport::default();
+    constraints.into_iter().for_each(|constraint| {
+        constraint.remove(grid);
+        if grid.solve(Some(2)).solutions == solutions {
+            report.removed.push(constraint);
+        } else {
+            constraint.apply(grid);
+            report.kept.push(constraint);
+        }
+    });
+    Ok(report)
+}
+
+impl Grid {
+    /// Greedily removes redundant symbols from this grid while keeping its solution unique.
+    /// See `minimizer::minimize`.
+    pub fn minimize(&mut self) -> Result<MinimizeReport, MinimizeError> {
+        minimize(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+
+    #[test]
+    fn minimized_puzzles_stay_unique() {
+        let mut generator = Generator::new(vec2!(3, 3), 21);
+        (0..4).for_each(|_| {
+            let mut puzzle = generator.generate_unique(200);
+            assert!(puzzle.report.is_unique(), "{:?}", puzzle.report);
+            // Over-constrain the puzzle first
+            (0..4).for_each(|_| {
+                generator.add_random_constraint(&mut puzzle.grid, &puzzle.solution);
+            });
+            let before = Constraint::get_all(&puzzle.grid).len();
+            let report = puzzle.grid.minimize().unwrap();
+            assert_eq!(report.kept.len() + report.removed.len(), before);
+            assert_eq!(Constraint::get_all(&puzzle.grid), report.kept);
+            assert_eq!(
+                puzzle.grid.solve(None).solutions,
+                vec![puzzle.solution.clone()]
+            );
+            // Every constraint that's left is actually needed to keep the solution
+            report.kept.iter().for_each(|constraint| {
+                constraint.remove(&mut puzzle.grid);
+                assert_ne!(
+                    puzzle.grid.solve(Some(2)).solutions,
+                    vec![puzzle.solution.clone()]
+                );
+                constraint.apply(&mut puzzle.grid);
+            });
+        });
+    }
+
+    #[test]
+    fn keep_constraints_that_change_the_solution() {
+        use crate::grid::{CellType, Color, Vertex};
+        // #k #w *k
+        // *k .  .
+        let mut grid = Grid::new(vec2!(3, 2));
+        grid.set_vertex_type(8, Vertex::Start);
+        grid.set_vertex_type(3, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(2, CellType::Square(Color::White));
+        grid.set_cell_type(4, CellType::Star(Color::Black));
+        grid.set_cell_type(1, CellType::Star(Color::Black));
+        let solution = grid.solve(Some(2)).solutions;
+        assert_eq!(solution[0].get_vertices(), &vec![8, 4, 5, 1, 2, 3]);
+
+        // Without the star in the bottom left corner there's still one solution, but it's a different one
+        let star = Constraint::Cell(1, CellType::Star(Color::Black));
+        let mut without_star = grid.clone();
+        star.remove(&mut without_star);
+        assert_eq!(without_star.count_solutions(2), 1);
+        assert_ne!(without_star.solve(Some(2)).solutions, solution);
+
+        let report = minimize_in_order(&mut grid, vec![star]).unwrap();
+        assert_eq!(report.kept, vec![star]);
+        assert_eq!(grid.solve(Some(2)).solutions, solution);
+    }
+
+    #[test]
+    fn refuse_puzzles_with_many_solutions() {
+        let mut grid = Grid::new(vec2!(2, 2));
+        grid.set_vertex_type(6, crate::grid::Vertex::Start);
+        grid.set_vertex_type(2, crate::grid::Vertex::Exit);
+        assert_eq!(grid.minimize(), Err(MinimizeError::NotUnique(2)));
+    }
+}
@@ -0,0 +1,413 @@
+//! Puzzle packs, which bundle puzzles together into a campaign.
+//!
+//! A pack is a text file that starts with the pack's details, followed by its puzzles in the order they're meant to
+//! be played in:
+//! ```plaintext
+//! title: Tutorial
+//! author: Someone
+//! description: A few puzzles to learn the basics.
+//!
+//! [first]
+//! name: First steps
+//!
+//! +---+---E
+//! |   |   |
+//! S---+---+
+//!
+//! [squares]
+//! name: Black and white
+//! requires: first
+//!
+//! +---+---E
+//! | #k| #w|
+//! S---+---+
+//! ```
+//! Every puzzle starts with its ID in square brackets, followed by its details, an empty line,
+//! and the puzzle itself in the format described in the `text` module.
+//! A puzzle's `name` defaults to its ID, and `requires` lists the IDs of the puzzles that have to be solved
+//! before it's unlocked, separated by commas. Puzzles that don't require anything are unlocked from the start.
+//! Only `title` is required of the pack's details.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    grid::Grid,
+    text::{self, ParseError},
+};
+
+/// A collection of puzzles, played in order, where solving puzzles unlocks later ones.
+#[derive(Debug, Clone)]
+pub struct PuzzlePack {
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    /// The puzzles in the pack, in the order they're recommended to be played in.
+    pub puzzles: Vec<PackPuzzle>,
+}
+
+/// A puzzle in a pack.
+#[derive(Debug, Clone)]
+pub struct PackPuzzle {
+    /// The name the pack uses to refer to this puzzle.
+    pub id: String,
+    /// The name shown to players.
+    pub name: String,
+    /// The IDs of the puzzles that have to be solved before this one is unlocked.
+    pub requires: Vec<String>,
+    pub grid: Grid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackErrorKind {
+    /// The pack doesn't have a title.
+    MissingTitle,
+    /// The pack doesn't have any puzzles.
+    NoPuzzles,
+    /// A line that should be `key: value` isn't.
+    ExpectedKeyValue,
+    /// A detail that packs or puzzles don't have.
+    UnknownKey(String),
+    /// A detail that was already given.
+    DuplicateKey(String),
+    /// A puzzle ID that's already used by another puzzle.
+    DuplicatePuzzle(String),
+    /// A puzzle requires a puzzle that isn't in the pack.
+    UnknownRequirement(String),
+    /// This puzzle requires itself, directly or through other puzzles, so it can never be unlocked.
+    RequirementCycle(String),
+    /// A puzzle couldn't be parsed. The line and column of the error are counted from the start of the pack.
+    Puzzle(ParseError),
+}
+
+/// An error in a pack, along with the line it's on, counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackError {
+    pub line: usize,
+    pub kind: PackErrorKind,
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            PackErrorKind::Puzzle(error) => return write!(f, "{error}"),
+            _ => write!(f, "line {}: ", self.line)?,
+        }
+        match &self.kind {
+            PackErrorKind::MissingTitle => write!(f, "the pack has no title"),
+            PackErrorKind::NoPuzzles => write!(f, "the pack has no puzzles"),
+            PackErrorKind::ExpectedKeyValue => write!(f, "expected a line like \"key: value\""),
+            PackErrorKind::UnknownKey(key) => write!(f, "unknown key {key:?}"),
+            PackErrorKind::DuplicateKey(key) => write!(f, "{key:?} is given more than once"),
+            PackErrorKind::DuplicatePuzzle(id) => {
+                write!(f, "there's more than one puzzle named {id:?}")
+            }
+            PackErrorKind::UnknownRequirement(id) => write!(f, "there's no puzzle named {id:?}"),
+            PackErrorKind::RequirementCycle(id) => {
+                write!(
+                    f,
+                    "puzzle {id:?} requires itself, so it can never be unlocked"
+                )
+            }
+            PackErrorKind::Puzzle(_) => unreachable!(),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+/// An error from loading a pack from a file.
+#[derive(Debug)]
+pub enum PackLoadError {
+    Io(std::io::Error),
+    Parse(PackError),
+}
+
+impl fmt::Display for PackLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackLoadError::Io(error) => write!(f, "{error}"),
+            PackLoadError::Parse(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for PackLoadError {}
+
+/// Splits a `key: value` line, giving `None` if it isn't one.
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    Some((key.trim(), value.trim()))
+}
+
+impl PuzzlePack {
+    /// Parses a pack from the format described in this module.
+    pub fn parse(pack: &str) -> Result<Self, PackError> {
+        let lines: Vec<&str> = pack.lines().collect();
+        let error = |line: usize, kind: PackErrorKind| PackError {
+            line: line + 1,
+            kind,
+        };
+        let is_puzzle_start = |line: &str| line.starts_with('[') && line.trim_end().ends_with(']');
+        // Reads `key: value` lines starting at `start`, until an empty line or the start of a puzzle
+        let read_details = |start: usize, keys: &[&str]| {
+            let mut details: HashMap<String, String> = HashMap::new();
+            let mut index = start;
+            while index < lines.len()
+                && !lines[index].trim().is_empty()
+                && !is_puzzle_start(lines[index])
+            {
+                let (key, value) = split_key_value(lines[index])
+                    .ok_or_else(|| error(index, PackErrorKind::ExpectedKeyValue))?;
+                if !keys.contains(&key) {
+                    return Err(error(index, PackErrorKind::UnknownKey(key.to_string())));
+                }
+                if details.insert(key.to_string(), value.to_string()).is_some() {
+                    return Err(error(index, PackErrorKind::DuplicateKey(key.to_string())));
+                }
+                index += 1;
+            }
+            Ok((details, index))
+        };
+
+        let mut index = 0;
+        while index < lines.len() && lines[index].trim().is_empty() {
+            index += 1;
+        }
+        let (mut details, end) = read_details(index, &["title", "author", "description"])?;
+        let title = details
+            .remove("title")
+            .ok_or_else(|| error(index, PackErrorKind::MissingTitle))?;
+        let mut pack = PuzzlePack {
+            title,
+            author: details.remove("author").unwrap_or_default(),
+            description: details.remove("description").unwrap_or_default(),
+            puzzles: vec![],
+        };
+        index = end;
+        // Lines with the IDs of every puzzle and what they require, for reporting errors
+        let mut puzzle_lines = vec![];
+        loop {
+            while index < lines.len() && lines[index].trim().is_empty() {
+                index += 1;
+            }
+            if index == lines.len() {
+                break;
+            }
+            if !is_puzzle_start(lines[index]) {
+                return Err(error(index, PackErrorKind::ExpectedKeyValue));
+            }
+            let id = lines[index].trim_end();
+            let id = id[1..id.len() - 1].trim().to_string();
+            if pack.puzzles.iter().any(|puzzle| puzzle.id == id) {
+                return Err(error(index, PackErrorKind::DuplicatePuzzle(id)));
+            }
+            let id_line = index;
+            let (mut details, end) = read_details(index + 1, &["name", "requires"])?;
+            let requires_line = (id_line + 1..end)
+                .find(|&line| lines[line].trim_start().starts_with("requires"))
+                .unwrap_or(id_line);
+            // The puzzle goes on until the next one starts
+            let grid_start = end;
+            index = grid_start;
+            while index < lines.len() && !is_puzzle_start(lines[index]) {
+                index += 1;
+            }
+            let grid_text = lines[grid_start..index]
+                .iter()
+                .skip_while(|line| line.trim().is_empty())
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            let blank_lines = lines[grid_start..index]
+                .iter()
+                .take_while(|line| line.trim().is_empty())
+                .count();
+            let grid = text::parse(&grid_text).map_err(|mut parse_error| {
+                parse_error.line += grid_start + blank_lines;
+                error(parse_error.line - 1, PackErrorKind::Puzzle(parse_error))
+            })?;
+            pack.puzzles.push(PackPuzzle {
+                name: details.remove("name").unwrap_or_else(|| id.clone()),
+                requires: details
+                    .remove("requires")
+                    .map(|requires| {
+                        requires
+                            .split(',')
+                            .map(|id| id.trim().to_string())
+                            .filter(|id| !id.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                id,
+                grid,
+            });
+            puzzle_lines.push((id_line, requires_line));
+        }
+        if pack.puzzles.is_empty() {
+            return Err(error(lines.len(), PackErrorKind::NoPuzzles));
+        }
+
+        for (puzzle, &(_, requires_line)) in pack.puzzles.iter().zip(&puzzle_lines) {
+            if let Some(unknown) = puzzle
+                .requires
+                .iter()
+                .find(|id| pack.get_puzzle_index(id).is_none())
+            {
+                return Err(error(
+                    requires_line,
+                    PackErrorKind::UnknownRequirement(unknown.clone()),
+                ));
+            }
+        }
+        // Every puzzle has to be unlockable once everything that can be solved is solved
+        let mut solved = HashSet::new();
+        while let Some(index) = (0..pack.puzzles.len())
+            .find(|&i| !solved.contains(&pack.puzzles[i].id) && pack.is_unlocked(i, &solved))
+        {
+            solved.insert(pack.puzzles[index].id.clone());
+        }
+        if let Some(index) =
+            (0..pack.puzzles.len()).find(|&i| !solved.contains(&pack.puzzles[i].id))
+        {
+            return Err(error(
+                puzzle_lines[index].0,
+                PackErrorKind::RequirementCycle(pack.puzzles[index].id.clone()),
+            ));
+        }
+        Ok(pack)
+    }
+
+    /// Loads a pack from a file.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, PackLoadError> {
+        let pack = std::fs::read_to_string(path).map_err(PackLoadError::Io)?;
+        Self::parse(&pack).map_err(PackLoadError::Parse)
+    }
+
+    /// Writes this pack in the format described in this module.
+    pub fn to_text(&self) -> String {
+        let mut pack = format!("title: {}\n", self.title);
+        if !self.author.is_empty() {
+            pack.push_str(&format!("author: {}\n", self.author));
+        }
+        if !self.description.is_empty() {
+            pack.push_str(&format!("description: {}\n", self.description));
+        }
+        self.puzzles.iter().for_each(|puzzle| {
+            pack.push_str(&format!("\n[{}]\n", puzzle.id));
+            if puzzle.name != puzzle.id {
+                pack.push_str(&format!("name: {}\n", puzzle.name));
+            }
+            if !puzzle.requires.is_empty() {
+                pack.push_str(&format!("requires: {}\n", puzzle.requires.join(", ")));
+            }
+            pack.push('\n');
+            pack.push_str(&puzzle.grid.to_text());
+        });
+        pack
+    }
+
+    /// Gets where the puzzle with the given ID is in the pack.
+    pub fn get_puzzle_index(&self, id: &str) -> Option<usize> {
+        self.puzzles.iter().position(|puzzle| puzzle.id == id)
+    }
+
+    /// Checks whether every puzzle the puzzle at `index` requires has been solved.
+    pub fn is_unlocked(&self, index: usize, solved: &HashSet<String>) -> bool {
+        self.puzzles
+            .get(index)
+            .is_some_and(|puzzle| puzzle.requires.iter().all(|id| solved.contains(id)))
+    }
+
+    /// Gets the first unlocked puzzle that hasn't been solved yet, looking from `from` onwards
+    /// and then wrapping around to the start of the pack.
+    pub fn get_next_unsolved(&self, from: usize, solved: &HashSet<String>) -> Option<usize> {
+        (from..self.puzzles.len())
+            .chain(0..from.min(self.puzzles.len()))
+            .find(|&index| {
+                !solved.contains(&self.puzzles[index].id) && self.is_unlocked(index, solved)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Vertex;
+
+    const PACK: &str = "\
+title: Tutorial
+author: Someone
+description: A few puzzles to learn the basics.
+
+[first]
+name: First steps
+
++---+---E
+|   |   |
+S---+---+
+
+[squares]
+name: Black and white
+requires: first
+
++---+---E
+| #k| #w|
+S---+---+
+
+[both]
+requires: first, squares
+
++---E
+| Pa|
+S---+
+
+a: #
+";
+
+    #[test]
+    fn parse_and_print() {
+        let pack = PuzzlePack::parse(PACK).unwrap();
+        assert_eq!(pack.title, "Tutorial");
+        assert_eq!(pack.puzzles.len(), 3);
+        assert_eq!(pack.puzzles[1].name, "Black and white");
+        assert_eq!(pack.puzzles[2].name, "both");
+        assert_eq!(pack.puzzles[2].requires, vec!["first", "squares"]);
+        assert_eq!(pack.puzzles[0].grid.get_vertex_type(3), Some(Vertex::Start));
+        assert_eq!(pack.to_text(), PACK);
+    }
+
+    #[test]
+    fn unlocking() {
+        let pack = PuzzlePack::parse(PACK).unwrap();
+        let mut solved = HashSet::new();
+        assert!(pack.is_unlocked(0, &solved));
+        assert!(!pack.is_unlocked(1, &solved));
+        assert_eq!(pack.get_next_unsolved(1, &solved), Some(0));
+        solved.insert("first".to_string());
+        assert_eq!(pack.get_next_unsolved(1, &solved), Some(1));
+        assert!(!pack.is_unlocked(2, &solved));
+        solved.insert("squares".to_string());
+        assert!(pack.is_unlocked(2, &solved));
+        solved.insert("both".to_string());
+        assert_eq!(pack.get_next_unsolved(0, &solved), None);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |pack: &str| PuzzlePack::parse(pack).unwrap_err();
+        assert_eq!(error("author: x\n").kind, PackErrorKind::MissingTitle);
+        assert_eq!(error("title: x\n").kind, PackErrorKind::NoPuzzles);
+        let unknown = error(&PACK.replace("requires: first\n", "requires: last\n"));
+        assert_eq!(
+            (unknown.line, unknown.kind),
+            (14, PackErrorKind::UnknownRequirement("last".into()))
+        );
+        let cycle = error(&PACK.replace("[first]\n", "[first]\nrequires: both\n"));
+        assert_eq!(cycle.kind, PackErrorKind::RequirementCycle("first".into()));
+        // Errors in puzzles point at the line in the pack
+        let puzzle = error(&PACK.replace("| #k|", "| #x|"));
+        assert_eq!(puzzle.to_string(), "line 17, column 4: unknown color 'x'");
+    }
+}
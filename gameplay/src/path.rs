@@ -0,0 +1,95 @@
+use crate::grid::{EdgeID, GridSnapshot, VertexID};
+
+/// A line drawn on a grid, stored as the vertices it passes through in the order they were visited.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Path {
+    vertices: Vec<VertexID>,
+}
+
+impl Path {
+    /// Creates a new, empty path.
+    pub fn new() -> Self {
+        Path { vertices: vec![] }
+    }
+
+    /// Creates a path that starts at the given vertex.
+    pub fn starting_at(start: VertexID) -> Self {
+        Path {
+            vertices: vec![start],
+        }
+    }
+
+    /// Creates a path that visits the given vertices in order.
+    /// This doesn't check whether the path is valid on any grid; see `GridSnapshot::check_path` for that.
+    pub fn from_vertices(vertices: Vec<VertexID>) -> Self {
+        Path { vertices }
+    }
+
+    /// Gets the vertices this path visits, in order.
+    pub fn get_vertices(&self) -> &Vec<VertexID> {
+        &self.vertices
+    }
+
+    /// Returns the vertex this path starts at, if it isn't empty.
+    pub fn first(&self) -> Option<VertexID> {
+        self.vertices.first().copied()
+    }
+
+    /// Returns the vertex this path currently ends at, if it isn't empty.
+    pub fn last(&self) -> Option<VertexID> {
+        self.vertices.last().copied()
+    }
+
+    /// Returns the amount of vertices this path visits.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Extends the path by one vertex.
+    pub fn push(&mut self, vertex: VertexID) {
+        self.vertices.push(vertex)
+    }
+
+    /// Removes the last vertex from the path and returns it.
+    pub fn pop(&mut self) -> Option<VertexID> {
+        self.vertices.pop()
+    }
+
+    /// Checks whether this path visits the given vertex.
+    pub fn contains_vertex(&self, vertex: VertexID) -> bool {
+        self.vertices.contains(&vertex)
+    }
+
+    /// Gets the IDs of the edges this path crosses, in order.
+    /// Returns `None` if two consecutive vertices in this path aren't connected by an edge.
+    pub fn get_edge_ids(&self, snapshot: &GridSnapshot) -> Option<Vec<EdgeID>> {
+        self.vertices
+            .windows(2)
+            .map(|pair| snapshot.get_edge_between(pair[0], pair[1]))
+            .collect()
+    }
+
+    /// Gets a mask of the edges this path crosses, indexed by edge ID.
+    /// Pairs of consecutive vertices that aren't connected by an edge are ignored.
+    pub fn get_used_edges(&self, snapshot: &GridSnapshot) -> Vec<bool> {
+        let mut used = vec![false; snapshot.edge_count()];
+        self.vertices.windows(2).for_each(|pair| {
+            if let Some(edge) = snapshot.get_edge_between(pair[0], pair[1]) {
+                used[edge] = true;
+            }
+        });
+        used
+    }
+}
+
+impl From<Vec<VertexID>> for Path {
+    fn from(vertices: Vec<VertexID>) -> Self {
+        Path::from_vertices(vertices)
+    }
+}
@@ -0,0 +1,70 @@
+/// A small, seedable pseudorandom number generator (SplitMix64).
+/// This is not suitable for anything security related, but it's fast, has no dependencies
+/// and always produces the same numbers for the same seed on every platform, including the 3DS.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Creates a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    /// Returns the next random 64-bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in the range `0..max`.
+    /// Panics if `max` is zero.
+    pub fn below(&mut self, max: usize) -> usize {
+        assert!(max != 0, "Can't pick a random number below zero");
+        (self.next_u64() % max as u64) as usize
+    }
+
+    /// Returns `true` with the given probability, which should be between 0 and 1.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        ((self.next_u64() >> 40) as f32 / (1u64 << 24) as f32) < probability
+    }
+
+    /// Picks a random element from a slice, if it isn't empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len())])
+        }
+    }
+
+    /// Shuffles a slice in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        (1..items.len()).rev().for_each(|i| {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Random::new(1234);
+        let mut b = Random::new(1234);
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut random = Random::new(5);
+        assert!((0..1000).all(|_| random.below(7) < 7));
+    }
+}
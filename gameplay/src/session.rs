@@ -0,0 +1,430 @@
+use crate::{
+    event::{EventQueue, GameEvent},
+    grid::{Grid, GridPoint},
+    history::{History, LineStep},
+    path::Path,
+    tracer::{LineTracer, TraceBlocker},
+    validation::{FailingSymbol, ValidationError},
+};
+
+/// How long a submitted line is shown before the result is revealed, in seconds.
+pub const SUBMIT_DURATION: f32 = 0.25;
+/// How long the failing symbols blink after a wrong line is submitted, in seconds.
+pub const FAILURE_BLINK_DURATION: f32 = 1.5;
+/// How long one blink of the failing symbols takes, on and off, in seconds.
+pub const FAILURE_BLINK_PERIOD: f32 = 0.3;
+/// How long an abandoned or failed line takes to fade out, in seconds.
+pub const FADE_OUT_DURATION: f32 = 0.5;
+
+/// Where a session is in the flow of solving a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionState {
+    /// No line is being drawn.
+    Idle,
+    /// The player is drawing a line.
+    Drawing,
+    /// The line reached an exit and was let go of; the result is revealed shortly.
+    Submitted,
+    /// The submitted line solves the puzzle. It stays on screen until a new line is started.
+    Solved,
+    /// The submitted line doesn't solve the puzzle. Its failing symbols blink, then the line fades out.
+    Failed,
+}
+
+/// A line that was let go of and is fading out.
+#[derive(Debug, Clone)]
+struct FadingLine {
+    polyline: Vec<GridPoint>,
+    time: f32,
+}
+
+/// Runs the flow of solving a single puzzle: drawing a line, submitting it, and showing the result.
+/// Frontends feed it input and elapsed time, and only have to render what it reports.
+#[derive(Debug, Clone)]
+pub struct PuzzleSession {
+    grid: Grid,
+    tracer: LineTracer,
+    state: SessionState,
+    /// How long the session has been in its current state, in seconds.
+    state_time: f32,
+    failing_symbols: Vec<FailingSymbol>,
+    fading_line: Option<FadingLine>,
+    /// The steps taken while drawing the current line.
+    line_history: History<LineStep>,
+    events: EventQueue,
+    /// What stopped the line the last time it was moved, so running into it again isn't reported twice.
+    blocker: Option<TraceBlocker>,
+    /// Whether the line was at an exit the last time it changed.
+    at_exit: bool,
+}
+
+impl PuzzleSession {
+    pub fn new(grid: Grid) -> Self {
+        PuzzleSession {
+            tracer: LineTracer::new(&grid),
+            grid,
+            state: SessionState::Idle,
+            state_time: 0.,
+            failing_symbols: vec![],
+            fading_line: None,
+            line_history: History::default(),
+            events: EventQueue::new(),
+            blocker: None,
+            at_exit: false,
+        }
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn get_tracer(&self) -> &LineTracer {
+        &self.tracer
+    }
+
+    pub fn get_state(&self) -> SessionState {
+        self.state
+    }
+
+    /// Gets how long the session has been in its current state, in seconds.
+    pub fn get_state_time(&self) -> f32 {
+        self.state_time
+    }
+
+    /// Gets the symbols the last submitted line failed.
+    /// This is empty unless the session is `Failed`.
+    pub fn get_failing_symbols(&self) -> &Vec<FailingSymbol> {
+        &self.failing_symbols
+    }
+
+    /// Gets the line to draw, in grid space. This is empty when there's no line.
+    pub fn get_line(&self) -> Vec<GridPoint> {
+        self.tracer.get_polyline()
+    }
+
+    /// Gets the path of the current line.
+    pub fn get_path(&self) -> &Path {
+        self.tracer.get_path()
+    }
+
+    /// Checks whether the failing symbols should currently be highlighted.
+    /// This alternates while the session is `Failed`, and is always `false` otherwise.
+    pub fn is_failure_blink_on(&self) -> bool {
+        self.state == SessionState::Failed
+            && ((self.state_time / (FAILURE_BLINK_PERIOD / 2.)) as usize).is_multiple_of(2)
+    }
+
+    /// Takes the oldest event that hasn't been handled yet.
+    pub fn poll_event(&mut self) -> Option<GameEvent> {
+        self.events.pop()
+    }
+
+    /// Takes every event that hasn't been handled yet, oldest first.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = GameEvent> + '_ {
+        self.events.drain()
+    }
+
+    /// Gets the line that's fading out, if any, along with how opaque it still is, from 1 down to 0.
+    pub fn get_fading_line(&self) -> Option<(&Vec<GridPoint>, f32)> {
+        self.fading_line.as_ref().map(|fading| {
+            (
+                &fading.polyline,
+                (1. - fading.time / FADE_OUT_DURATION).clamp(0., 1.),
+            )
+        })
+    }
+
+    /// Starts a new line at the start closest to the given point, if there is one within the given distance.
+    /// This works from any state except `Drawing` and `Submitted`, and throws away the previous result.
+    pub fn start_near(&mut self, point: GridPoint, max_distance: f32) -> bool {
+        if matches!(self.state, SessionState::Drawing | SessionState::Submitted) {
+            return false;
+        }
+        let previous = self.tracer.get_polyline();
+        if !self.tracer.start_near(point, max_distance) {
+            return false;
+        }
+        if self.state == SessionState::Failed {
+            self.fade_out(previous);
+        }
+        self.failing_symbols.clear();
+        self.line_history.clear();
+        self.blocker = None;
+        self.at_exit = false;
+        self.set_state(SessionState::Drawing);
+        self.events.push(GameEvent::LineStarted(
+            self.tracer.get_path().first().unwrap(),
+        ));
+        true
+    }
+
+    /// Moves the line by a cursor movement in grid space. See `LineTracer::move_by`.
+    pub fn move_by(&mut self, delta: GridPoint) -> Option<TraceBlocker> {
+        if self.state != SessionState::Drawing {
+            return None;
+        }
+        let before = self.tracer.get_path().clone();
+        let blocker = self.tracer.move_by(delta);
+        LineStep::get_steps_between(&before, self.tracer.get_path())
+            .into_iter()
+            .for_each(|step| {
+                self.line_history.record(step);
+                self.push_line_step_event(step);
+            });
+        if let Some(blocker) = blocker.filter(|&blocker| self.blocker != Some(blocker)) {
+            self.events.push(GameEvent::LineBlocked(blocker));
+        }
+        self.blocker = blocker;
+        self.check_exit();
+        blocker
+    }
+
+    /// Takes back the last step of the line being drawn, putting its head back on a vertex.
+    /// Returns `false` if there was nothing to undo.
+    pub fn undo_line_step(&mut self) -> bool {
+        self.change_line(|history, path| history.undo(path))
+    }
+
+    /// Takes the last undone step of the line being drawn again.
+    /// Returns `false` if there was nothing to redo.
+    pub fn redo_line_step(&mut self) -> bool {
+        self.change_line(|history, path| history.redo(path))
+    }
+
+    fn change_line(
+        &mut self,
+        change: impl FnOnce(&mut History<LineStep>, &mut Path) -> bool,
+    ) -> bool {
+        if self.state != SessionState::Drawing {
+            return false;
+        }
+        let before = self.tracer.get_path().clone();
+        let mut path = before.clone();
+        if !change(&mut self.line_history, &mut path) || !self.tracer.set_path(path) {
+            return false;
+        }
+        LineStep::get_steps_between(&before, self.tracer.get_path())
+            .into_iter()
+            .for_each(|step| self.push_line_step_event(step));
+        self.blocker = None;
+        self.check_exit();
+        true
+    }
+
+    fn push_line_step_event(&mut self, step: LineStep) {
+        self.events.push(match step {
+            LineStep::Extend(vertex) => GameEvent::LineExtended(vertex),
+            LineStep::Retract(vertex) => GameEvent::LineRetracted(vertex),
+        });
+    }
+
+    /// Reports the line reaching an exit, once each time it gets there.
+    fn check_exit(&mut self) {
+        let at_exit = self.tracer.is_at_exit();
+        if at_exit && !self.at_exit {
+            self.events.push(GameEvent::ReachedExit(
+                self.tracer.get_path().last().unwrap(),
+            ));
+        }
+        self.at_exit = at_exit;
+    }
+
+    /// Lets go of the line. If it reached the end of an exit, it's submitted; otherwise it's abandoned.
+    pub fn release(&mut self) {
+        if self.state != SessionState::Drawing {
+            return;
+        }
+        if !self.tracer.is_at_exit() {
+            self.abandon();
+            return;
+        }
+        self.failing_symbols = match self.tracer.get_snapshot().validate(self.tracer.get_path()) {
+            Ok(()) => vec![],
+            Err(ValidationError::FailingSymbols(symbols)) => symbols,
+            // A line the tracer drew to an exit can always be drawn
+            Err(ValidationError::InvalidPath(error)) => {
+                unreachable!("the tracer drew an invalid path: {error:?}")
+            }
+        };
+        self.set_state(SessionState::Submitted);
+        self.events.push(GameEvent::LineSubmitted);
+    }
+
+    /// Throws away the line being drawn, fading it out.
+    pub fn abandon(&mut self) {
+        if self.state != SessionState::Drawing {
+            return;
+        }
+        self.fade_out(self.tracer.get_polyline());
+        self.tracer.clear();
+        self.set_state(SessionState::Idle);
+        self.events.push(GameEvent::LineAbandoned);
+    }
+
+    /// Advances the session's timers by the given amount of seconds.
+    pub fn update(&mut self, delta_time: f32) {
+        self.state_time += delta_time;
+        if let Some(fading) = &mut self.fading_line {
+            fading.time += delta_time;
+            if fading.time >= FADE_OUT_DURATION {
+                self.fading_line = None;
+            }
+        }
+        match self.state {
+            SessionState::Submitted if self.state_time >= SUBMIT_DURATION => {
+                if self.failing_symbols.is_empty() {
+                    self.set_state(SessionState::Solved);
+                    self.events.push(GameEvent::Solved);
+                } else {
+                    self.set_state(SessionState::Failed);
+                    self.events.push(GameEvent::Failed);
+                    self.failing_symbols
+                        .iter()
+                        .for_each(|&symbol| self.events.push(GameEvent::SymbolFailed(symbol)));
+                }
+            }
+            SessionState::Failed if self.state_time >= FAILURE_BLINK_DURATION => {
+                self.fade_out(self.tracer.get_polyline());
+                self.tracer.clear();
+                self.failing_symbols.clear();
+                self.set_state(SessionState::Idle);
+            }
+            _ => {}
+        }
+    }
+
+    fn set_state(&mut self, state: SessionState) {
+        self.state = state;
+        self.state_time = 0.;
+    }
+
+    fn fade_out(&mut self, polyline: Vec<GridPoint>) {
+        if !polyline.is_empty() {
+            self.fading_line = Some(FadingLine { polyline, time: 0. });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{CellType, Color, EdgeType, Vertex};
+
+    fn session() -> PuzzleSession {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        PuzzleSession::new(grid)
+    }
+
+    /// Draws a line along the given movements and lets go of it.
+    fn draw(session: &mut PuzzleSession, moves: &[(f32, f32)]) {
+        assert!(session.start_near(vec2!(0., 1.), 0.5));
+        assert_eq!(session.get_state(), SessionState::Drawing);
+        moves.iter().for_each(|&(x, y)| {
+            session.move_by(vec2!(x, y));
+        });
+        session.release();
+    }
+
+    #[test]
+    fn solve() {
+        let mut session = session();
+        draw(&mut session, &[(1., 0.), (0., -1.), (1., 0.), (1., 0.)]);
+        assert_eq!(session.get_state(), SessionState::Submitted);
+        session.update(SUBMIT_DURATION);
+        assert_eq!(session.get_state(), SessionState::Solved);
+        assert!(!session.is_failure_blink_on());
+        // The solution stays up
+        session.update(10.);
+        assert_eq!(session.get_state(), SessionState::Solved);
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 4, 1, 2]);
+    }
+
+    #[test]
+    fn fail_and_fade() {
+        let mut session = session();
+        draw(&mut session, &[(0., -1.), (1., 0.), (1., 0.), (1., 0.)]);
+        session.update(SUBMIT_DURATION);
+        assert_eq!(session.get_state(), SessionState::Failed);
+        assert_eq!(session.get_failing_symbols().len(), 2);
+        assert!(session.is_failure_blink_on());
+        session.update(FAILURE_BLINK_PERIOD / 2.);
+        assert!(!session.is_failure_blink_on());
+        session.update(FAILURE_BLINK_DURATION);
+        assert_eq!(session.get_state(), SessionState::Idle);
+        assert!(session.get_failing_symbols().is_empty());
+        assert!(session.get_line().is_empty());
+        assert_eq!(session.get_fading_line().unwrap().1, 1.);
+        session.update(FADE_OUT_DURATION);
+        assert!(session.get_fading_line().is_none());
+    }
+
+    #[test]
+    fn undo_line_steps() {
+        let mut session = session();
+        session.start_near(vec2!(0., 1.), 0.5);
+        session.move_by(vec2!(1.5, 0.));
+        session.move_by(vec2!(0.5, 0.));
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 4, 5]);
+        // Backing up is a step too
+        session.move_by(vec2!(-1., 0.));
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 4]);
+        assert!(session.undo_line_step());
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 4, 5]);
+        assert!(session.undo_line_step());
+        assert!(session.undo_line_step());
+        assert!(!session.undo_line_step());
+        assert_eq!(session.get_line(), vec![vec2!(0., 1.)]);
+        assert!(session.redo_line_step());
+        assert_eq!(session.get_path().get_vertices(), &vec![3, 4]);
+    }
+
+    #[test]
+    fn events() {
+        let mut grid = session().get_grid().clone();
+        let gap = grid.get_edge_id_between(0, 1).unwrap();
+        grid.set_edge_type(gap, EdgeType::Gap);
+        let mut gapped = PuzzleSession::new(grid);
+        draw(&mut gapped, &[(0., -1.), (1., 0.), (0.1, 0.), (1., 0.)]);
+        // Pushing against the gap twice only reports it once
+        assert_eq!(
+            gapped.drain_events().collect::<Vec<_>>(),
+            vec![
+                GameEvent::LineStarted(3),
+                GameEvent::LineExtended(0),
+                GameEvent::LineBlocked(TraceBlocker::Gap(gap)),
+                GameEvent::LineAbandoned,
+            ]
+        );
+
+        let mut session = session();
+        draw(&mut session, &[(0., -1.), (1., 0.), (1., 0.), (1., 0.)]);
+        session.update(SUBMIT_DURATION);
+        let events: Vec<GameEvent> = session.drain_events().collect();
+        assert_eq!(
+            events[3..],
+            [
+                GameEvent::LineExtended(2),
+                GameEvent::ReachedExit(2),
+                GameEvent::LineSubmitted,
+                GameEvent::Failed,
+                GameEvent::SymbolFailed(FailingSymbol::Cell(0)),
+                GameEvent::SymbolFailed(FailingSymbol::Cell(1)),
+            ]
+        );
+        assert_eq!(session.poll_event(), None);
+    }
+
+    #[test]
+    fn abandon() {
+        let mut session = session();
+        draw(&mut session, &[(1., 0.)]);
+        assert_eq!(session.get_state(), SessionState::Idle);
+        assert_eq!(session.get_fading_line().unwrap().0.len(), 2);
+        session.update(FADE_OUT_DURATION / 2.);
+        assert_eq!(session.get_fading_line().unwrap().1, 0.5);
+    }
+}
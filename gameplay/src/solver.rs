@@ -0,0 +1,347 @@
+use crate::{
+    grid::{Grid, GridSnapshot, Vertex, VertexID},
+    path::Path,
+};
+
+/// Statistics about how much work the solver had to do to find its solutions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolverStats {
+    /// The amount of partial paths the solver looked at.
+    pub nodes_visited: usize,
+    /// The amount of times the solver got stuck on a vertex it couldn't continue from and had to back up.
+    pub dead_ends: usize,
+    /// The amount of paths that went from a start to an exit, whether they were solutions or not.
+    pub complete_paths: usize,
+    /// The length of the longest partial path the solver looked at, measured in vertices.
+    pub max_depth: usize,
+    /// The total amount of moves the solver tried, summed over every vertex it visited.
+    pub branches: usize,
+    /// The most vertices the solver ever had to back up in one go before it found a new move to try.
+    pub max_backtrack_depth: usize,
+    /// The amount of paths from a start to an exit that only failed a single symbol.
+    pub near_solutions: usize,
+}
+
+impl SolverStats {
+    /// The average amount of moves the solver could choose from at every vertex it didn't get stuck on.
+    pub fn get_branching_factor(&self) -> f32 {
+        let expanded = self.nodes_visited.saturating_sub(self.dead_ends);
+        if expanded == 0 {
+            0.
+        } else {
+            self.branches as f32 / expanded as f32
+        }
+    }
+}
+
+/// The outcome of running the solver on a grid.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveResult {
+    /// The solutions that were found.
+    pub solutions: Vec<Path>,
+    /// How much work it took to find them.
+    pub stats: SolverStats,
+    /// Whether every possible path was looked at.
+    /// This is `false` if the solver stopped early because it found as many solutions as it was asked for,
+    /// or because it ran out of nodes to visit.
+    pub exhaustive: bool,
+}
+
+/// Finds solutions to a grid by trying every path from every start.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    snapshot: GridSnapshot,
+}
+
+impl Solver {
+    /// Creates a solver for the given grid.
+    /// The solver works on a snapshot, so changes made to the grid afterwards won't be picked up.
+    pub fn new(grid: &Grid) -> Self {
+        Solver {
+            snapshot: grid.snapshot(),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: GridSnapshot) -> Self {
+        Solver { snapshot }
+    }
+
+    pub fn get_snapshot(&self) -> &GridSnapshot {
+        &self.snapshot
+    }
+
+    /// Searches for solutions.
+    /// If a limit is given, the search stops as soon as that many solutions have been found.
+    pub fn solve(&self, limit: Option<usize>) -> SolveResult {
+        self.search(limit, None)
+    }
+
+    /// Searches for solutions like `solve`, but gives up after visiting the given amount of nodes.
+    /// This keeps the time spent on grids with huge amounts of paths bounded, e.g. while editing them.
+    /// Whether every path was looked at can be told from `SolveResult::exhaustive`.
+    pub fn solve_within(&self, limit: Option<usize>, node_budget: usize) -> SolveResult {
+        self.search(limit, Some(node_budget))
+    }
+
+    fn search(&self, limit: Option<usize>, node_budget: Option<usize>) -> SolveResult {
+        let mut search = Search {
+            snapshot: &self.snapshot,
+            limit,
+            node_budget,
+            path: Path::new(),
+            visited: vec![false; self.snapshot.vertex_count()],
+            used_edges: vec![false; self.snapshot.edge_count()],
+            solutions: vec![],
+            stats: SolverStats::default(),
+            last_dead_end_depth: None,
+        };
+        let exhaustive = self
+            .snapshot
+            .get_all_vertex_ids_iter()
+            .filter(|&v| self.snapshot.get_vertex_type(v) == Some(Vertex::Start))
+            .all(|start| search.visit(start));
+        SolveResult {
+            solutions: search.solutions,
+            stats: search.stats,
+            exhaustive,
+        }
+    }
+
+    /// Searches for solutions that begin with the given partial path.
+    /// The path has to be drawable and begin at a start, see `GridSnapshot::check_path`; otherwise nothing is found.
+    /// If a limit is given, the search stops as soon as that many solutions have been found.
+    pub fn solve_from(&self, prefix: &Path, limit: Option<usize>) -> SolveResult {
+        let mut search = Search {
+            snapshot: &self.snapshot,
+            limit,
+            path: Path::new(),
+            visited: vec![false; self.snapshot.vertex_count()],
+            node_budget: None,
+            used_edges: prefix.get_used_edges(&self.snapshot),
+            solutions: vec![],
+            stats: SolverStats::default(),
+            last_dead_end_depth: None,
+        };
+        let exhaustive = match self.snapshot.check_path(prefix, false) {
+            Ok(()) => {
+                let (&last, rest) = prefix.get_vertices().split_last().unwrap();
+                rest.iter().for_each(|&v| {
+                    search.path.push(v);
+                    search.visited[v] = true;
+                });
+                search.visit(last)
+            }
+            Err(_) => true,
+        };
+        SolveResult {
+            solutions: search.solutions,
+            stats: search.stats,
+            exhaustive,
+        }
+    }
+
+    /// Counts the solutions, stopping at the given limit.
+    /// Asking for a limit of 2 is the cheapest way to find out whether a puzzle has exactly one solution.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.solve(Some(limit)).solutions.len()
+    }
+}
+
+impl Grid {
+    /// Searches for solutions to this grid.
+    /// If a limit is given, the search stops as soon as that many solutions have been found.
+    pub fn solve(&self, limit: Option<usize>) -> SolveResult {
+        Solver::new(self).solve(limit)
+    }
+
+    /// Counts the solutions to this grid, stopping at the given limit.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        Solver::new(self).count_solutions(limit)
+    }
+
+    /// Searches for solutions to this grid, giving up after visiting the given amount of nodes.
+    /// See `Solver::solve_within`.
+    pub fn solve_within(&self, limit: Option<usize>, node_budget: usize) -> SolveResult {
+        Solver::new(self).solve_within(limit, node_budget)
+    }
+}
+
+/// The state of a single depth-first search.
+struct Search<'a> {
+    snapshot: &'a GridSnapshot,
+    limit: Option<usize>,
+    /// The most nodes to visit before giving up.
+    node_budget: Option<usize>,
+    path: Path,
+    visited: Vec<bool>,
+    used_edges: Vec<bool>,
+    solutions: Vec<Path>,
+    stats: SolverStats,
+    /// How long the path was when the solver last got stuck, if it hasn't tried a new move since.
+    last_dead_end_depth: Option<usize>,
+}
+
+impl Search<'_> {
+    /// Extends the path to the given vertex and explores everything past it.
+    /// Returns `false` if the search should stop.
+    fn visit(&mut self, vertex: VertexID) -> bool {
+        if self.node_budget == Some(self.stats.nodes_visited) {
+            return false;
+        }
+        let snapshot = self.snapshot;
+        if let Some(dead_end_depth) = self.last_dead_end_depth.take() {
+            // The path is currently one vertex shorter than it was where it branched off
+            self.stats.max_backtrack_depth = self
+                .stats
+                .max_backtrack_depth
+                .max(dead_end_depth - self.path.len());
+        }
+        self.path.push(vertex);
+        self.visited[vertex] = true;
+        self.stats.nodes_visited += 1;
+        self.stats.max_depth = self.stats.max_depth.max(self.path.len());
+
+        let mut keep_going = true;
+        if snapshot.get_vertex_type(vertex) == Some(Vertex::Exit) {
+            self.stats.complete_paths += 1;
+            match snapshot
+                .get_failing_symbols(&self.visited, &self.used_edges)
+                .len()
+            {
+                0 => {
+                    self.solutions.push(self.path.clone());
+                    keep_going = self.limit != Some(self.solutions.len());
+                }
+                1 => self.stats.near_solutions += 1,
+                _ => {}
+            }
+        }
+
+        let mut moved = false;
+        for (edge, next) in snapshot.get_neighbours_iter(vertex) {
+            if !keep_going {
+                break;
+            }
+            if self.visited[next] {
+                continue;
+            }
+            moved = true;
+            self.stats.branches += 1;
+            self.used_edges[edge] = true;
+            keep_going = self.visit(next);
+            self.used_edges[edge] = false;
+        }
+        if !moved {
+            self.stats.dead_ends += 1;
+            self.last_dead_end_depth = Some(self.path.len());
+        }
+
+        self.visited[vertex] = false;
+        self.path.pop();
+        keep_going
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{CellType, Color};
+
+    #[test]
+    fn solve_empty_grid() {
+        // A 1x1 grid has exactly two paths from one corner to the opposite one
+        let mut grid = Grid::new(vec2!(1, 1));
+        grid.set_vertex_type(2, Vertex::Start);
+        grid.set_vertex_type(1, Vertex::Exit);
+        let result = grid.solve(None);
+        assert_eq!(result.solutions.len(), 2);
+        assert!(result.exhaustive);
+        assert!(result
+            .solutions
+            .iter()
+            .all(|path| grid.validate(path).is_ok()));
+    }
+
+    #[test]
+    fn solve_squares() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        // Only the paths through the middle edge keep the two colors apart
+        let result = grid.solve(None);
+        assert_eq!(result.solutions.len(), 2);
+        assert!(result
+            .solutions
+            .contains(&Path::from_vertices(vec![3, 4, 1, 2])));
+        assert!(result
+            .solutions
+            .contains(&Path::from_vertices(vec![3, 0, 1, 4, 5, 2])));
+    }
+
+    #[test]
+    fn solve_from_partial_path() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        let solver = Solver::new(&grid);
+        let result = solver.solve_from(&Path::from_vertices(vec![3, 0]), None);
+        assert_eq!(
+            result.solutions,
+            vec![Path::from_vertices(vec![3, 0, 1, 4, 5, 2])]
+        );
+        // Going right first can never separate the two colors
+        let result = solver.solve_from(&Path::from_vertices(vec![3, 4, 5]), None);
+        assert!(result.solutions.is_empty());
+        assert!(result.exhaustive);
+        // Paths that can't be drawn don't have any solutions
+        let result = solver.solve_from(&Path::from_vertices(vec![3, 1]), None);
+        assert!(result.solutions.is_empty());
+    }
+
+    #[test]
+    fn solve_stops_at_limit() {
+        let mut grid = Grid::new(vec2!(3, 3));
+        grid.set_vertex_type(12, Vertex::Start);
+        grid.set_vertex_type(3, Vertex::Exit);
+        let result = grid.solve(Some(2));
+        assert_eq!(result.solutions.len(), 2);
+        assert!(!result.exhaustive);
+        assert_eq!(grid.count_solutions(2), 2);
+    }
+
+    #[test]
+    fn solve_within_budget() {
+        let mut grid = Grid::new(vec2!(3, 3));
+        grid.set_vertex_type(12, Vertex::Start);
+        grid.set_vertex_type(3, Vertex::Exit);
+        let all = grid.solve(None);
+        let result = grid.solve_within(None, 20);
+        assert_eq!(result.stats.nodes_visited, 20);
+        assert!(!result.exhaustive);
+        assert!(result.solutions.len() < all.solutions.len());
+        // A budget that's big enough changes nothing
+        let result = grid.solve_within(None, all.stats.nodes_visited);
+        assert!(result.exhaustive);
+        assert_eq!(result.solutions, all.solutions);
+    }
+
+    #[test]
+    fn solver_stats() {
+        let mut grid = Grid::new(vec2!(1, 1));
+        grid.set_vertex_type(2, Vertex::Start);
+        grid.set_vertex_type(1, Vertex::Exit);
+        grid.set_vertex_type(0, Vertex::Dot);
+        let stats = grid.solve(None).stats;
+        // Both ways around reach the exit, but only one of them passes the dot
+        assert_eq!(stats.complete_paths, 2);
+        assert_eq!(stats.near_solutions, 1);
+        assert_eq!(stats.max_depth, 4);
+        assert!(stats.get_branching_factor() >= 1.);
+    }
+}
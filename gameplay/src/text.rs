@@ -0,0 +1,600 @@
+//! A plain-text puzzle format, in the same style as the grids drawn in the doc comment on `Grid::get_size`.
+//!
+//! ```plaintext
+//! +---o---+---E
+//! | #k|   | Pa|
+//! +- -+---+---+
+//! | ^2o *y| #w|
+//! S---+-o-+---+
+//!
+//! a: ##/#. rotatable
+//! ```
+//!
+//! The grid is drawn with every vertex on a `+`-like character, with three characters for every edge and cell in between.
+//! A grid that's `w` cells wide and `h` cells tall takes up `2h + 1` lines of `4w + 1` characters.
+//! Spaces at the end of a line can be left out.
+//!
+//! Vertices are drawn as:
+//! - `+` for a plain vertex
+//! - `S` for a start
+//! - `E` for an exit
+//! - `o` for a dot
+//! - a space for a vertex that doesn't exist
+//!
+//! Horizontal edges are drawn as `---`, `-o-` for a dot, and `- -` for a gap.
+//! Vertical edges are drawn as `|`, `o` for a dot, and a space for a gap.
+//!
+//! Cells are drawn as three spaces when they're blank, or as a space followed by a two character symbol:
+//! - `#c` for a square of color `c`
+//! - `*c` for a star of color `c`
+//! - `^1`, `^2` or `^3` for triangles
+//! - `Pi` for polyomino `i`
+//!
+//! Colors are written as `k` (black), `w` (white), `c` (cyan), `m` (magenta), `y` (yellow), `r` (red), `g` (green),
+//! `b` (blue) or `o` (orange).
+//!
+//! A row of blank cells with gaps on both sides of every cell is an empty line.
+//!
+//! Polyominoes are described below the grid, after an empty line, with one line per polyomino.
+//! Each line has the polyomino's name, a colon, and its shape written row by row from the top,
+//! with `#` for a covered square, `.` for an uncovered one, and `/` between rows.
+//! If the polyomino may be rotated, the line ends with ` rotatable`.
+//! Names are single letters or digits. Every polyomino has to be described exactly once, and every description used.
+//!
+//! Printing a grid gives the same text back that was parsed, as long as the text had its polyominoes named
+//! `a`, `b`, `c`... in the order they first show up when reading the grid from the top left,
+//! and had no spaces at the ends of its lines.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::grid::{
+    polyomino::POLYOMINO_MAX_SIZE, CellType, Color, EdgeType, Grid, GridVector2, Polyomino,
+    TriangleCount, Vertex,
+};
+
+/// The names polyominoes get when printing, in order.
+const POLYOMINO_NAMES: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+const COLORS: [(char, Color); 9] = [
+    ('k', Color::Black),
+    ('w', Color::White),
+    ('c', Color::Cyan),
+    ('m', Color::Magenta),
+    ('y', Color::Yellow),
+    ('r', Color::Red),
+    ('g', Color::Green),
+    ('b', Color::Blue),
+    ('o', Color::Orange),
+];
+
+/// Describes what's wrong with a puzzle text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The grid needs at least three lines: a row of vertices, a row of cells, and another row of vertices.
+    TooFewLines,
+    /// The grid has an even amount of lines, so it doesn't end on a row of vertices.
+    EvenLineCount,
+    /// The grid is too narrow to have any cells.
+    TooNarrow,
+    /// Something other than what was expected was found.
+    UnexpectedText {
+        found: String,
+        expected: &'static str,
+    },
+    /// A color letter that doesn't stand for any color.
+    UnknownColor(char),
+    /// A cell uses a polyomino that isn't described below the grid.
+    UndescribedPolyomino(char),
+    /// A polyomino is described more than once.
+    DuplicatePolyomino(char),
+    /// A polyomino is described but never used.
+    UnusedPolyomino(char),
+    /// A polyomino shape that's empty, has rows of different lengths, or is larger than 4x4.
+    InvalidPolyominoShape,
+}
+
+/// An error in a puzzle text, along with where it is. Lines and columns are counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::TooFewLines => write!(f, "a grid needs at least 3 lines"),
+            ParseErrorKind::EvenLineCount => {
+                write!(
+                    f,
+                    "a grid needs an odd amount of lines, ending with a row of vertices"
+                )
+            }
+            ParseErrorKind::TooNarrow => write!(f, "a grid needs to be at least 5 characters wide"),
+            ParseErrorKind::UnexpectedText { found, expected } => {
+                write!(f, "expected {expected}, found {found:?}")
+            }
+            ParseErrorKind::UnknownColor(color) => write!(f, "unknown color {color:?}"),
+            ParseErrorKind::UndescribedPolyomino(name) => {
+                write!(f, "polyomino {name:?} isn't described below the grid")
+            }
+            ParseErrorKind::DuplicatePolyomino(name) => {
+                write!(f, "polyomino {name:?} is described more than once")
+            }
+            ParseErrorKind::UnusedPolyomino(name) => write!(f, "polyomino {name:?} isn't used"),
+            ParseErrorKind::InvalidPolyominoShape => write!(
+                f,
+                "polyomino shapes need rows of equal length, at least one square, and to fit in 4x4"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a grid from the text format described in this module.
+pub fn parse(text: &str) -> Result<Grid, ParseError> {
+    let lines: Vec<&str> = text.lines().collect();
+    // A row of blank cells between gaps is an empty line too, but unlike the line before the polyominoes,
+    // it's followed by a row of vertices, which is never empty and has no colon in it
+    let is_blank = |line: usize| lines[line].trim_end().is_empty();
+    let is_vertex_row =
+        |line: usize| line < lines.len() && !is_blank(line) && !lines[line].contains(':');
+    let grid_line_count = (0..lines.len())
+        .find(|&line| is_blank(line) && (line % 2 == 0 || !is_vertex_row(line + 1)))
+        .unwrap_or(lines.len());
+    let error = |line: usize, column: usize, kind: ParseErrorKind| ParseError {
+        line: line + 1,
+        column: column + 1,
+        kind,
+    };
+    if grid_line_count < 3 {
+        return Err(error(grid_line_count, 0, ParseErrorKind::TooFewLines));
+    }
+    if grid_line_count % 2 == 0 {
+        return Err(error(grid_line_count - 1, 0, ParseErrorKind::EvenLineCount));
+    }
+    let longest = lines[..grid_line_count]
+        .iter()
+        .map(|line| line.trim_end().chars().count())
+        .max()
+        .unwrap();
+    if longest < 5 {
+        return Err(error(0, longest, ParseErrorKind::TooNarrow));
+    }
+    let size: GridVector2 = vec2!((longest - 1).div_ceil(4), (grid_line_count - 1) / 2);
+    let width = 4 * size.x + 1;
+    let rows: Vec<Vec<char>> = lines[..grid_line_count]
+        .iter()
+        .map(|line| {
+            let mut chars: Vec<char> = line.trim_end().chars().collect();
+            chars.resize(width, ' ');
+            chars
+        })
+        .collect();
+    let text_at = |line: usize, column: usize, length: usize| -> String {
+        rows[line][column..column + length].iter().collect()
+    };
+    let unexpected = |line: usize, column: usize, length: usize, expected: &'static str| {
+        error(
+            line,
+            column,
+            ParseErrorKind::UnexpectedText {
+                found: text_at(line, column, length),
+                expected,
+            },
+        )
+    };
+
+    let mut grid = Grid::new(size);
+    // Cells with polyominoes, by name, along with where they were found
+    let mut polyomino_cells: Vec<(char, usize, usize, usize)> = vec![];
+    for y in 0..=size.y {
+        let line = 2 * y;
+        for x in 0..=size.x {
+            let vertex = match rows[line][4 * x] {
+                '+' => Vertex::Vertex,
+                'S' => Vertex::Start,
+                'E' => Vertex::Exit,
+                'o' => Vertex::Dot,
+                ' ' => Vertex::None,
+                _ => {
+                    return Err(unexpected(
+                        line,
+                        4 * x,
+                        1,
+                        "a vertex (+, S, E, o or a space)",
+                    ));
+                }
+            };
+            grid.set_vertex_type(grid.get_vertex_id_at(vec2!(x, y)).unwrap(), vertex);
+            if x < size.x {
+                let kind = match text_at(line, 4 * x + 1, 3).as_str() {
+                    "---" => EdgeType::Edge,
+                    "-o-" => EdgeType::Dot,
+                    "- -" => EdgeType::Gap,
+                    _ => {
+                        return Err(unexpected(
+                            line,
+                            4 * x + 1,
+                            3,
+                            "a horizontal edge (---, -o- or - -)",
+                        ));
+                    }
+                };
+                set_edge_between(&mut grid, vec2!(x, y), vec2!(x + 1, y), kind);
+            }
+        }
+        if y == size.y {
+            break;
+        }
+        let line = 2 * y + 1;
+        for x in 0..=size.x {
+            let kind = match rows[line][4 * x] {
+                '|' => EdgeType::Edge,
+                'o' => EdgeType::Dot,
+                ' ' => EdgeType::Gap,
+                _ => {
+                    return Err(unexpected(
+                        line,
+                        4 * x,
+                        1,
+                        "a vertical edge (|, o or a space)",
+                    ));
+                }
+            };
+            set_edge_between(&mut grid, vec2!(x, y), vec2!(x, y + 1), kind);
+            if x == size.x {
+                break;
+            }
+            let column = 4 * x + 1;
+            let cell = grid.get_cell_id_at(vec2!(x, y)).unwrap();
+            let symbol = &rows[line][column..column + 3];
+            let kind = match symbol {
+                [' ', ' ', ' '] => CellType::Blank,
+                [' ', '#', color] | [' ', '*', color] => {
+                    let color = COLORS
+                        .iter()
+                        .find(|(letter, _)| letter == color)
+                        .ok_or(error(
+                            line,
+                            column + 2,
+                            ParseErrorKind::UnknownColor(*color),
+                        ))?
+                        .1;
+                    if symbol[1] == '#' {
+                        CellType::Square(color)
+                    } else {
+                        CellType::Star(color)
+                    }
+                }
+                [' ', '^', count @ '1'..='3'] => {
+                    CellType::Triangle(TriangleCount::try_from(*count as u8 - b'0').unwrap())
+                }
+                [' ', 'P', name] if name.is_ascii_alphanumeric() => {
+                    polyomino_cells.push((*name, cell, line, column + 2));
+                    CellType::Blank
+                }
+                _ => {
+                    return Err(unexpected(
+                        line,
+                        column,
+                        3,
+                        "a cell (three spaces, or a space followed by #c, *c, ^n or Pi)",
+                    ));
+                }
+            };
+            grid.set_cell_type(cell, kind);
+        }
+    }
+
+    // Read the polyomino descriptions
+    let mut polyominoes: HashMap<char, (Polyomino, usize)> = HashMap::new();
+    for (index, line) in lines.iter().enumerate().skip(grid_line_count) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let mut chars = line.chars();
+        let name = chars.next().unwrap();
+        if !name.is_ascii_alphanumeric() || chars.next() != Some(':') || chars.next() != Some(' ') {
+            return Err(error(
+                index,
+                0,
+                ParseErrorKind::UnexpectedText {
+                    found: line.to_string(),
+                    expected: "a polyomino description (a name, a colon and a shape)",
+                },
+            ));
+        }
+        let description = &line[3..];
+        let (shape, rotatable) = match description.strip_suffix(" rotatable") {
+            Some(shape) => (shape, true),
+            None => (description, false),
+        };
+        let polyomino = parse_polyomino_shape(shape, rotatable).ok_or(error(
+            index,
+            3,
+            ParseErrorKind::InvalidPolyominoShape,
+        ))?;
+        if polyominoes.insert(name, (polyomino, index)).is_some() {
+            return Err(error(index, 0, ParseErrorKind::DuplicatePolyomino(name)));
+        }
+    }
+    for &(name, cell, line, column) in &polyomino_cells {
+        let (polyomino, _) = polyominoes.get(&name).ok_or(error(
+            line,
+            column,
+            ParseErrorKind::UndescribedPolyomino(name),
+        ))?;
+        grid.set_cell_type(cell, CellType::Polyomino(*polyomino));
+    }
+    if let Some((&name, &(_, index))) = polyominoes
+        .iter()
+        .filter(|(name, _)| !polyomino_cells.iter().any(|cell| cell.0 == **name))
+        .min_by_key(|(_, (_, index))| *index)
+    {
+        return Err(error(index, 0, ParseErrorKind::UnusedPolyomino(name)));
+    }
+    Ok(grid)
+}
+
+fn set_edge_between(grid: &mut Grid, a: GridVector2, b: GridVector2, kind: EdgeType) {
+    let edge = grid
+        .get_edge_id_between(
+            grid.get_vertex_id_at(a).unwrap(),
+            grid.get_vertex_id_at(b).unwrap(),
+        )
+        .unwrap();
+    grid.set_edge_type(edge, kind);
+}
+
+/// Parses a polyomino shape like `##/#.`.
+fn parse_polyomino_shape(shape: &str, rotatable: bool) -> Option<Polyomino> {
+    let rows: Vec<&str> = shape.split('/').collect();
+    let width = rows[0].len();
+    if rows.len() > POLYOMINO_MAX_SIZE
+        || width > POLYOMINO_MAX_SIZE
+        || rows.iter().any(|row| row.len() != width)
+    {
+        return None;
+    }
+    let mut squares = vec![];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, square) in row.chars().enumerate() {
+            match square {
+                '#' => squares.push(vec2!(x, y)),
+                '.' => {}
+                _ => return None,
+            }
+        }
+    }
+    Polyomino::new(&squares, rotatable)
+}
+
+/// Writes a grid in the text format described in this module.
+pub fn print(grid: &Grid) -> String {
+    let size = grid.get_size();
+    let edge_between = |a: GridVector2, b: GridVector2| {
+        grid.get_edge_type(
+            grid.get_edge_id_between(
+                grid.get_vertex_id_at(a).unwrap(),
+                grid.get_vertex_id_at(b).unwrap(),
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let mut names: Vec<Polyomino> = vec![];
+    let mut lines: Vec<String> = vec![];
+    for y in 0..=size.y {
+        let mut line = String::new();
+        for x in 0..=size.x {
+            line.push(
+                match grid
+                    .get_vertex_type(grid.get_vertex_id_at(vec2!(x, y)).unwrap())
+                    .unwrap()
+                {
+                    Vertex::Vertex => '+',
+                    Vertex::Start => 'S',
+                    Vertex::Exit => 'E',
+                    Vertex::Dot => 'o',
+                    Vertex::None => ' ',
+                },
+            );
+            if x < size.x {
+                line.push_str(match edge_between(vec2!(x, y), vec2!(x + 1, y)) {
+                    EdgeType::Edge => "---",
+                    EdgeType::Dot => "-o-",
+                    EdgeType::Gap => "- -",
+                });
+            }
+        }
+        lines.push(line);
+        if y == size.y {
+            break;
+        }
+        let mut line = String::new();
+        for x in 0..=size.x {
+            line.push(match edge_between(vec2!(x, y), vec2!(x, y + 1)) {
+                EdgeType::Edge => '|',
+                EdgeType::Dot => 'o',
+                EdgeType::Gap => ' ',
+            });
+            if x == size.x {
+                break;
+            }
+            let kind = grid
+                .get_cell_type(grid.get_cell_id_at(vec2!(x, y)).unwrap())
+                .unwrap();
+            let color_letter =
+                |color: Color| COLORS.iter().find(|(_, other)| *other == color).unwrap().0;
+            match kind {
+                CellType::Blank => line.push_str("   "),
+                CellType::Square(color) => line.extend([' ', '#', color_letter(color)]),
+                CellType::Star(color) => line.extend([' ', '*', color_letter(color)]),
+                CellType::Triangle(count) => line.extend([' ', '^', (b'0' + count as u8) as char]),
+                CellType::Polyomino(polyomino) => {
+                    let index = names
+                        .iter()
+                        .position(|&other| other == polyomino)
+                        .unwrap_or_else(|| {
+                            names.push(polyomino);
+                            names.len() - 1
+                        });
+                    let name = POLYOMINO_NAMES
+                        .chars()
+                        .nth(index)
+                        .expect("too many different polyominoes to name");
+                    line.extend([' ', 'P', name]);
+                }
+            }
+        }
+        lines.push(line);
+    }
+    let mut text: String = lines
+        .iter()
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect();
+    if !names.is_empty() {
+        text.push('\n');
+        names
+            .iter()
+            .zip(POLYOMINO_NAMES.chars())
+            .for_each(|(polyomino, name)| {
+                let polyomino_size = polyomino.get_size();
+                let squares = polyomino.get_squares();
+                let shape = (0..polyomino_size.y)
+                    .map(|y| {
+                        (0..polyomino_size.x)
+                            .map(|x| {
+                                if squares.contains(&vec2!(x, y)) {
+                                    '#'
+                                } else {
+                                    '.'
+                                }
+                            })
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<String>>()
+                    .join("/");
+                let rotatable = if polyomino.is_rotatable() {
+                    " rotatable"
+                } else {
+                    ""
+                };
+                text.push_str(&format!("{name}: {shape}{rotatable}\n"));
+            });
+    }
+    text
+}
+
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    /// Parses a grid from the text format described in the `text` module.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse(text)
+    }
+}
+
+impl Grid {
+    /// Writes this grid in the text format described in the `text` module.
+    pub fn to_text(&self) -> String {
+        print(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
++---o---+---E
+| #k|   | Pa|
++- -+---+---+
+| ^2o *y| Pb|
+S---+-o-+- -
+| *y  Pa  ^3
++---+---+---+
+
+a: ##/#. rotatable
+b: ###
+";
+
+    #[test]
+    fn round_trip() {
+        let grid: Grid = EXAMPLE.parse().unwrap();
+        assert_eq!(grid.get_size(), vec2!(3, 3));
+        assert_eq!(grid.get_vertex_type(0), Some(Vertex::Vertex));
+        assert_eq!(grid.get_vertex_type(3), Some(Vertex::Exit));
+        assert_eq!(grid.get_vertex_type(8), Some(Vertex::Start));
+        assert_eq!(grid.get_vertex_type(11), Some(Vertex::None));
+        assert_eq!(
+            grid.get_cell_type(grid.get_cell_id_at(vec2!(0, 1)).unwrap()),
+            Some(CellType::Triangle(TriangleCount::Two))
+        );
+        let Some(CellType::Polyomino(polyomino)) =
+            grid.get_cell_type(grid.get_cell_id_at(vec2!(2, 0)).unwrap())
+        else {
+            panic!("expected a polyomino");
+        };
+        assert!(polyomino.is_rotatable());
+        assert_eq!(polyomino.get_area(), 3);
+        assert_eq!(grid.to_text(), EXAMPLE);
+        // A grid made in code can be printed and read back
+        let reparsed: Grid = grid.to_text().parse().unwrap();
+        assert_eq!(reparsed.to_text(), EXAMPLE);
+    }
+
+    #[test]
+    fn round_trip_rows_of_gaps() {
+        let mut grid = Grid::new(vec2!(1, 1));
+        grid.set_vertex_type(2, Vertex::Start);
+        grid.set_vertex_type(1, Vertex::Exit);
+        for (a, b) in [(0, 2), (1, 3)] {
+            grid.set_edge_type(grid.get_edge_id_between(a, b).unwrap(), EdgeType::Gap);
+        }
+        let text = grid.to_text();
+        assert_eq!(text, "+---E\n\nS---+\n");
+        assert_eq!(parse(&text).unwrap().to_text(), text);
+        // Even with polyominoes described below the grid
+        let text = "+---+---+\n| Pa|   |\n+---+---E\n\nS---+---+\n\na: #\n";
+        assert_eq!(parse(text).unwrap().to_text(), text);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |text: &str| text.parse::<Grid>().unwrap_err();
+        assert_eq!(error("+---+\n|   |").kind, ParseErrorKind::TooFewLines);
+        assert_eq!(
+            error("+---+\n|   |\n+---+\n|   |").kind,
+            ParseErrorKind::EvenLineCount
+        );
+        let unexpected = error("+---+\n| x |\n+---+");
+        assert_eq!((unexpected.line, unexpected.column), (2, 2));
+        assert_eq!(
+            unexpected.to_string(),
+            "line 2, column 2: expected a cell (three spaces, or a space followed by #c, *c, ^n or Pi), found \" x \""
+        );
+        let color = error("+---+\n| #x|\n+-=-+");
+        assert_eq!((color.line, color.column), (2, 4));
+        assert_eq!(color.kind, ParseErrorKind::UnknownColor('x'));
+        let edge = error("+---+\n|   |\n+-=-+");
+        assert_eq!((edge.line, edge.column), (3, 2));
+        assert_eq!(
+            error("+---+\n| Pa|\n+---+").kind,
+            ParseErrorKind::UndescribedPolyomino('a')
+        );
+        let unused = error("+---+\n| Pa|\n+---+\n\na: #\nb: ##");
+        assert_eq!(
+            (unused.line, unused.kind),
+            (6, ParseErrorKind::UnusedPolyomino('b'))
+        );
+        assert_eq!(
+            error("+---+\n| Pa|\n+---+\n\na: ##/#").kind,
+            ParseErrorKind::InvalidPolyominoShape
+        );
+    }
+}
@@ -0,0 +1,446 @@
+use crate::{
+    grid::{EdgeID, EdgeType, Grid, GridPoint, GridSnapshot, Vertex, VertexID},
+    path::Path,
+};
+
+/// How far past an exit its nub sticks out, in grid units.
+pub const EXIT_NUB_LENGTH: f32 = 0.25;
+/// How far into an edge with a gap the line can go before it's stopped, as a fraction of the edge.
+pub const GAP_LIMIT: f32 = 0.35;
+/// How close the line can get to its own body, as a fraction of the edge it's on.
+pub const BODY_LIMIT: f32 = 0.75;
+/// The most edges a single move can cross. This keeps huge cursor jumps from locking up a frame.
+const MAX_MOVE_STEPS: usize = 32;
+/// How close to the end of an edge counts as being at the end, to make up for rounding errors.
+const SNAP_EPSILON: f32 = 1e-4;
+
+/// What stopped the line from following the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceBlocker {
+    /// The line ran into a gap on this edge.
+    Gap(EdgeID),
+    /// The line ran into a vertex it already visits.
+    Body(VertexID),
+    /// The line ran into a vertex that doesn't exist.
+    MissingVertex(VertexID),
+}
+
+/// Where the head of the line is, relative to the last vertex of the path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Head {
+    /// Exactly on the last vertex.
+    AtVertex,
+    /// Part of the way along the edge from the last vertex to this one, as a fraction of the edge.
+    OnEdge { to: VertexID, progress: f32 },
+    /// Part of the way into the nub of the exit the line is on, as a fraction of the nub.
+    InNub { progress: f32 },
+}
+
+/// Follows the cursor along the edges of a grid to draw the line, the way the game does.
+/// The tracer knows nothing about screens; frontends turn mouse, touch or stick movement into
+/// deltas in grid space and feed them to `move_by`.
+#[derive(Debug, Clone)]
+pub struct LineTracer {
+    snapshot: GridSnapshot,
+    path: Path,
+    head: Head,
+}
+
+impl LineTracer {
+    pub fn new(grid: &Grid) -> Self {
+        Self::from_snapshot(grid.snapshot())
+    }
+
+    pub fn from_snapshot(snapshot: GridSnapshot) -> Self {
+        LineTracer {
+            snapshot,
+            path: Path::new(),
+            head: Head::AtVertex,
+        }
+    }
+
+    pub fn get_snapshot(&self) -> &GridSnapshot {
+        &self.snapshot
+    }
+
+    /// Starts a new line at the given start, throwing away the current one.
+    /// Returns `false` and leaves the current line alone if the vertex isn't a start.
+    pub fn start_at(&mut self, start: VertexID) -> bool {
+        if self.snapshot.get_vertex_type(start) != Some(Vertex::Start) {
+            return false;
+        }
+        self.path = Path::starting_at(start);
+        self.head = Head::AtVertex;
+        true
+    }
+
+    /// Starts a new line at the start closest to the given point, if there is one within the given distance.
+    /// This is what clicking or tapping on a start does.
+    pub fn start_near(&mut self, point: GridPoint, max_distance: f32) -> bool {
+        let closest = self
+            .snapshot
+            .get_all_vertex_ids_iter()
+            .filter(|&v| self.snapshot.get_vertex_type(v) == Some(Vertex::Start))
+            .map(|v| (v, (self.get_vertex_point(v) - point).mag()))
+            .filter(|&(_, distance)| distance <= max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match closest {
+            Some((start, _)) => self.start_at(start),
+            None => false,
+        }
+    }
+
+    /// Replaces the current line with the given path, with the head on its last vertex.
+    /// Returns `false` and leaves the current line alone if the path can't be drawn or doesn't begin at a start.
+    pub fn set_path(&mut self, path: Path) -> bool {
+        if self.snapshot.check_path(&path, false).is_err() {
+            return false;
+        }
+        self.path = path;
+        self.head = Head::AtVertex;
+        true
+    }
+
+    /// Throws away the current line.
+    pub fn clear(&mut self) {
+        self.path = Path::new();
+        self.head = Head::AtVertex;
+    }
+
+    /// Checks whether a line is being drawn.
+    pub fn is_drawing(&self) -> bool {
+        !self.path.is_empty()
+    }
+
+    /// Gets the vertices the line has fully reached, from its start onwards.
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Checks whether the line has been drawn all the way to the end of an exit's nub.
+    /// Exits that don't sit on the border of the grid don't have a nub, so reaching them is enough.
+    pub fn is_at_exit(&self) -> bool {
+        let Some(last) = self.path.last() else {
+            return false;
+        };
+        if self.snapshot.get_vertex_type(last) != Some(Vertex::Exit) {
+            return false;
+        }
+        match (self.head, self.get_nub_direction(last)) {
+            (Head::InNub { progress }, _) => progress >= 1.,
+            (Head::AtVertex, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Gets the edge the head of the line is partway along, and how far along it is.
+    /// The progress is measured from the last vertex of the path.
+    pub fn get_current_edge(&self) -> Option<(EdgeID, f32)> {
+        match self.head {
+            Head::OnEdge { to, progress } => Some((
+                self.snapshot.get_edge_between(self.path.last()?, to)?,
+                progress,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Gets where the head of the line is, in grid space.
+    pub fn get_head_point(&self) -> Option<GridPoint> {
+        let last = self.get_vertex_point(self.path.last()?);
+        Some(match self.head {
+            Head::AtVertex => last,
+            Head::OnEdge { to, progress } => last.lerp(self.get_vertex_point(to), progress),
+            Head::InNub { progress } => {
+                last + self.get_nub_direction(self.path.last()?)? * (progress * EXIT_NUB_LENGTH)
+            }
+        })
+    }
+
+    /// Gets the line as a list of points in grid space, ready to be drawn as connected segments.
+    /// This is empty if no line is being drawn.
+    pub fn get_polyline(&self) -> Vec<GridPoint> {
+        let mut points: Vec<GridPoint> = self
+            .path
+            .get_vertices()
+            .iter()
+            .map(|&v| self.get_vertex_point(v))
+            .collect();
+        if self.head != Head::AtVertex {
+            points.extend(self.get_head_point());
+        }
+        points
+    }
+
+    /// Moves the head of the line by a cursor movement in grid space.
+    /// Movement is snapped to the edge the line is on, and turns onto whichever edge the movement points along the most
+    /// whenever the head reaches a vertex. Moving back along the line erases it.
+    /// Returns what stopped the line, if it was stopped.
+    pub fn move_by(&mut self, delta: GridPoint) -> Option<TraceBlocker> {
+        if !self.is_drawing() {
+            return None;
+        }
+        let mut remaining = delta;
+        for _ in 0..MAX_MOVE_STEPS {
+            if remaining.mag() <= f32::EPSILON {
+                break;
+            }
+            let last = self.path.last().unwrap();
+            match self.head {
+                Head::AtVertex => {
+                    if !self.turn(remaining) {
+                        break;
+                    }
+                }
+                Head::OnEdge { to, progress } => {
+                    let direction = self.get_vertex_point(to) - self.get_vertex_point(last);
+                    let along = Self::get_movement_along(remaining, direction, progress);
+                    let (limit, blocker) = self.get_edge_limit(last, to);
+                    let target = progress + along;
+                    if target >= limit && limit < 1. {
+                        self.head = Head::OnEdge {
+                            to,
+                            progress: limit,
+                        };
+                        return blocker;
+                    } else if target >= 1. - SNAP_EPSILON {
+                        remaining *= (1. - (1. - progress) / along).max(0.);
+                        self.path.push(to);
+                        self.head = Head::AtVertex;
+                    } else if target <= SNAP_EPSILON {
+                        remaining *= (1. - progress / -along).max(0.);
+                        self.head = Head::AtVertex;
+                    } else {
+                        self.head = Head::OnEdge {
+                            to,
+                            progress: target,
+                        };
+                        break;
+                    }
+                }
+                Head::InNub { progress } => {
+                    let direction = self.get_nub_direction(last).unwrap();
+                    let along =
+                        Self::get_movement_along(remaining, direction, progress) / EXIT_NUB_LENGTH;
+                    let target = progress + along;
+                    if target <= SNAP_EPSILON {
+                        remaining *= (1. - progress / -along).max(0.);
+                        self.head = Head::AtVertex;
+                    } else {
+                        self.head = Head::InNub {
+                            progress: if target >= 1. - SNAP_EPSILON {
+                                1.
+                            } else {
+                                target
+                            },
+                        };
+                        break;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Picks the way to go from the vertex the head is on, and steps onto it without moving yet.
+    /// Picking the edge the line came from backs the line up instead.
+    /// Returns `false` if there's nowhere to go in the direction of the movement.
+    fn turn(&mut self, movement: GridPoint) -> bool {
+        let last = self.path.last().unwrap();
+        let here = self.get_vertex_point(last);
+        let mut best: Option<(Head, f32)> = None;
+        let mut consider = |head: Head, direction: GridPoint| {
+            let score = movement.dot(direction);
+            if score > 0. && best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((head, score));
+            }
+        };
+        self.snapshot
+            .get_edges_of_vertex(last)
+            .iter()
+            .filter_map(|&e| self.snapshot.get_edge(e).unwrap().get_other_vertex(last))
+            .for_each(|to| {
+                consider(
+                    Head::OnEdge { to, progress: 0. },
+                    self.get_vertex_point(to) - here,
+                )
+            });
+        if self.snapshot.get_vertex_type(last) == Some(Vertex::Exit) {
+            if let Some(direction) = self.get_nub_direction(last) {
+                consider(Head::InNub { progress: 0. }, direction);
+            }
+        }
+        match best {
+            Some((Head::OnEdge { to, .. }, _)) if self.is_previous_vertex(to) => {
+                self.path.pop();
+                self.head = Head::OnEdge {
+                    to: last,
+                    progress: 1.,
+                };
+                true
+            }
+            Some((head, _)) => {
+                self.head = head;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets how far a movement goes along a direction.
+    /// Movement that's mostly sideways slides the head towards the closest end instead,
+    /// so the line can go around corners without the cursor having to line up exactly.
+    fn get_movement_along(movement: GridPoint, direction: GridPoint, progress: f32) -> f32 {
+        let along = movement.dot(direction);
+        let sideways = movement.cross(direction).abs();
+        if sideways > along.abs() {
+            if progress < 0.5 {
+                -sideways
+            } else {
+                sideways
+            }
+        } else {
+            along
+        }
+    }
+
+    /// Gets how far the line can go along the edge from one vertex to another, and what stops it there.
+    fn get_edge_limit(&self, from: VertexID, to: VertexID) -> (f32, Option<TraceBlocker>) {
+        let edge = self.snapshot.get_edge_between(from, to).unwrap();
+        if self.snapshot.get_edge_type(edge) == Some(EdgeType::Gap) {
+            (GAP_LIMIT, Some(TraceBlocker::Gap(edge)))
+        } else if self.snapshot.get_vertex_type(to) == Some(Vertex::None) {
+            (GAP_LIMIT, Some(TraceBlocker::MissingVertex(to)))
+        } else if self.path.contains_vertex(to) {
+            (BODY_LIMIT, Some(TraceBlocker::Body(to)))
+        } else {
+            (1., None)
+        }
+    }
+
+    fn is_previous_vertex(&self, vertex: VertexID) -> bool {
+        let vertices = self.path.get_vertices();
+        vertices.len() >= 2 && vertices[vertices.len() - 2] == vertex
+    }
+
+    fn get_vertex_point(&self, vertex: VertexID) -> GridPoint {
+        vec2cast!(self.snapshot.get_vertex_position(vertex).unwrap(), f32)
+    }
+
+    /// Gets the direction the nub of an exit points in, which is straight out of the grid.
+    /// Exits that aren't on the border don't have a nub.
+    fn get_nub_direction(&self, exit: VertexID) -> Option<GridPoint> {
+        get_exit_nub_direction(&self.snapshot, exit)
+    }
+}
+
+/// Gets the direction the nub of an exit points in, which is straight out of the grid.
+/// Exits that aren't on the border don't have a nub.
+pub fn get_exit_nub_direction(snapshot: &GridSnapshot, exit: VertexID) -> Option<GridPoint> {
+    let position = snapshot.get_vertex_position(exit)?;
+    let size = snapshot.get_size();
+    if position.x == 0 {
+        Some(vec2!(-1., 0.))
+    } else if position.x == size.x {
+        Some(vec2!(1., 0.))
+    } else if position.y == 0 {
+        Some(vec2!(0., -1.))
+    } else if position.y == size.y {
+        Some(vec2!(0., 1.))
+    } else {
+        None
+    }
+}
+
+/// Gets the points a finished path passes through in grid space,
+/// running all the way to the end of the exit's nub if the path ends at an exit.
+pub fn get_path_polyline(snapshot: &GridSnapshot, path: &Path) -> Vec<GridPoint> {
+    let mut points: Vec<GridPoint> = path
+        .get_vertices()
+        .iter()
+        .filter_map(|&v| snapshot.get_vertex_position(v))
+        .map(|position| vec2cast!(position, f32))
+        .collect();
+    if let Some(last) = path.last() {
+        if snapshot.get_vertex_type(last) == Some(Vertex::Exit) {
+            if let Some(direction) = get_exit_nub_direction(snapshot, last) {
+                points.push(*points.last().unwrap() + direction * EXIT_NUB_LENGTH);
+            }
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x1 grid with a start in the bottom left and an exit in the top right.
+    fn tracer() -> (Grid, LineTracer) {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        let tracer = LineTracer::new(&grid);
+        (grid, tracer)
+    }
+
+    #[test]
+    fn follow_and_snap() {
+        let (_, mut tracer) = tracer();
+        assert!(!tracer.start_near(vec2!(1., 0.), 0.5));
+        assert!(tracer.start_near(vec2!(0.2, 0.9), 0.5));
+        // Slightly diagonal movement still snaps to the edge
+        tracer.move_by(vec2!(0.5, 0.1));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3]);
+        assert_eq!(tracer.get_head_point(), Some(vec2!(0.5, 1.)));
+        // One big movement crosses several edges and turns up at the end
+        tracer.move_by(vec2!(1.5, -0.5));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3, 4, 5]);
+        assert_eq!(tracer.get_polyline().len(), 3);
+        tracer.move_by(vec2!(0., -1.));
+        tracer.move_by(vec2!(0., -1.));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3, 4, 5, 2]);
+        assert!(!tracer.is_at_exit());
+        // The exit nub points right, out of the grid
+        tracer.move_by(vec2!(1., 0.));
+        assert!(tracer.is_at_exit());
+        assert_eq!(
+            tracer.get_head_point(),
+            Some(vec2!(2. + EXIT_NUB_LENGTH, 0.))
+        );
+    }
+
+    #[test]
+    fn backtrack() {
+        let (_, mut tracer) = tracer();
+        tracer.start_at(3);
+        tracer.move_by(vec2!(2., 0.));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3, 4, 5]);
+        tracer.move_by(vec2!(-1.5, 0.));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3]);
+        assert_eq!(tracer.get_head_point(), Some(vec2!(0.5, 1.)));
+        // Backing up past the start doesn't do anything
+        tracer.move_by(vec2!(-3., 0.));
+        assert_eq!(tracer.get_polyline(), vec![vec2!(0., 1.)]);
+    }
+
+    #[test]
+    fn blocked_by_gaps_and_body() {
+        let (mut grid, _) = tracer();
+        let gap = grid.get_edge_id_between(4, 5).unwrap();
+        grid.set_edge_type(gap, EdgeType::Gap);
+        let mut tracer = LineTracer::new(&grid);
+        tracer.start_at(3);
+        assert_eq!(tracer.move_by(vec2!(2., 0.)), Some(TraceBlocker::Gap(gap)));
+        assert_eq!(tracer.get_current_edge(), Some((gap, GAP_LIMIT)));
+
+        // Moving sideways slides back to the vertex and turns there
+        tracer.move_by(vec2!(0., -1.));
+        tracer.move_by(vec2!(0., -1.));
+        tracer.move_by(vec2!(-1., 0.));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3, 4, 1, 0]);
+        // Going around the left cell runs into the start
+        assert_eq!(tracer.move_by(vec2!(0., 1.)), Some(TraceBlocker::Body(3)));
+        assert_eq!(tracer.get_path().get_vertices(), &vec![3, 4, 1, 0]);
+    }
+}
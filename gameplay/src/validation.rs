@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use crate::{
+    grid::{
+        polyomino::can_tile_region, CellID, CellType, Color, EdgeID, EdgeType, Grid, GridSnapshot,
+        GridVector2, Polyomino, Vertex, VertexID,
+    },
+    path::Path,
+};
+
+/// Describes why a path can't be a solution to a grid at all, regardless of the symbols on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathError {
+    /// The path doesn't visit any vertices.
+    Empty,
+    /// The path visits a vertex that doesn't exist on the grid.
+    MissingVertex(VertexID),
+    /// The first vertex of the path isn't a start.
+    DoesNotStartAtStart(VertexID),
+    /// The last vertex of the path doesn't have an exit.
+    DoesNotEndAtExit(VertexID),
+    /// Two consecutive vertices in the path aren't connected by an edge.
+    NotConnected(VertexID, VertexID),
+    /// The path crosses a gap.
+    CrossesGap(EdgeID),
+    /// The path visits the same vertex more than once.
+    VisitsVertexTwice(VertexID),
+}
+
+/// A symbol on the grid that isn't satisfied by a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailingSymbol {
+    /// A dot on a vertex that the path doesn't visit.
+    VertexDot(VertexID),
+    /// A dot on an edge that the path doesn't cross.
+    EdgeDot(EdgeID),
+    /// The symbol in a cell.
+    Cell(CellID),
+}
+
+/// Describes why a path isn't a solution to a grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The path can't be drawn on the grid or doesn't go from a start to an exit.
+    InvalidPath(PathError),
+    /// The path can be drawn, but some symbols aren't satisfied by it.
+    FailingSymbols(Vec<FailingSymbol>),
+}
+
+impl GridSnapshot {
+    /// Checks whether a path can be drawn on this grid, without looking at any symbols.
+    /// If `complete` is false the path only needs to begin at a start, and doesn't have to end at an exit yet.
+    pub fn check_path(&self, path: &Path, complete: bool) -> Result<(), PathError> {
+        let vertices = path.get_vertices();
+        let first = path.first().ok_or(PathError::Empty)?;
+        let mut visited = vec![false; self.vertex_count()];
+        for &vertex in vertices {
+            match self.get_vertex_type(vertex) {
+                None | Some(Vertex::None) => return Err(PathError::MissingVertex(vertex)),
+                Some(_) if visited[vertex] => return Err(PathError::VisitsVertexTwice(vertex)),
+                Some(_) => visited[vertex] = true,
+            }
+        }
+        if self.get_vertex_type(first) != Some(Vertex::Start) {
+            return Err(PathError::DoesNotStartAtStart(first));
+        }
+        for pair in vertices.windows(2) {
+            let edge = self
+                .get_edge_between(pair[0], pair[1])
+                .ok_or(PathError::NotConnected(pair[0], pair[1]))?;
+            if self.get_edge_type(edge) == Some(EdgeType::Gap) {
+                return Err(PathError::CrossesGap(edge));
+            }
+        }
+        let last = path.last().unwrap();
+        if complete && self.get_vertex_type(last) != Some(Vertex::Exit) {
+            return Err(PathError::DoesNotEndAtExit(last));
+        }
+        Ok(())
+    }
+
+    /// Returns the symbols that aren't satisfied when the line visits and crosses the given vertices and edges.
+    /// Both masks are indexed by ID.
+    pub fn get_failing_symbols(
+        &self,
+        visited_vertices: &[bool],
+        used_edges: &[bool],
+    ) -> Vec<FailingSymbol> {
+        let mut failing: Vec<FailingSymbol> = self
+            .get_all_vertex_ids_iter()
+            .filter(|&v| self.get_vertex_type(v) == Some(Vertex::Dot) && !visited_vertices[v])
+            .map(FailingSymbol::VertexDot)
+            .collect();
+        failing.extend(
+            self.get_all_edge_ids_iter()
+                .filter(|&e| self.get_edge_type(e) == Some(EdgeType::Dot) && !used_edges[e])
+                .map(FailingSymbol::EdgeDot),
+        );
+        self.get_regions(used_edges).iter().for_each(|region| {
+            failing.extend(
+                self.get_failing_cells_in_region(region, used_edges)
+                    .into_iter()
+                    .map(FailingSymbol::Cell),
+            )
+        });
+        failing
+    }
+
+    /// Returns the cells in a region whose symbols aren't satisfied.
+    fn get_failing_cells_in_region(&self, region: &[CellID], used_edges: &[bool]) -> Vec<CellID> {
+        let mut color_counts: HashMap<Color, usize> = HashMap::new();
+        let mut square_colors: Vec<Color> = vec![];
+        let mut polyominoes: Vec<Polyomino> = vec![];
+        region.iter().for_each(|&cell| {
+            let kind = self.get_cell_type(cell).unwrap();
+            if let CellType::Polyomino(polyomino) = kind {
+                polyominoes.push(polyomino);
+            }
+            if let Some(color) = kind.get_color() {
+                *color_counts.entry(color).or_default() += 1;
+            }
+            if let CellType::Square(color) = kind {
+                if !square_colors.contains(&color) {
+                    square_colors.push(color);
+                }
+            }
+        });
+        let polyominoes_fit = polyominoes.is_empty() || {
+            let positions: Vec<GridVector2> = region
+                .iter()
+                .map(|&cell| self.get_cell_position(cell).unwrap())
+                .collect();
+            can_tile_region(&positions, &polyominoes)
+        };
+        region
+            .iter()
+            .copied()
+            .filter(|&cell| match self.get_cell_type(cell).unwrap() {
+                CellType::Blank => false,
+                CellType::Square(_) => square_colors.len() > 1,
+                CellType::Star(color) => color_counts[&color] != 2,
+                CellType::Triangle(count) => {
+                    self.get_edges_of_cell(cell)
+                        .iter()
+                        .filter(|&&e| used_edges[e])
+                        .count()
+                        != count as usize
+                }
+                CellType::Polyomino(_) => !polyominoes_fit,
+            })
+            .collect()
+    }
+
+    /// Checks whether the given path is a solution to this grid.
+    pub fn validate(&self, path: &Path) -> Result<(), ValidationError> {
+        self.check_path(path, true)
+            .map_err(ValidationError::InvalidPath)?;
+        let mut visited = vec![false; self.vertex_count()];
+        path.get_vertices().iter().for_each(|&v| visited[v] = true);
+        let failing = self.get_failing_symbols(&visited, &path.get_used_edges(self));
+        if failing.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::FailingSymbols(failing))
+        }
+    }
+}
+
+impl Grid {
+    /// Checks whether the given path is a solution to this grid.
+    pub fn validate(&self, path: &Path) -> Result<(), ValidationError> {
+        self.snapshot().validate(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::TriangleCount;
+
+    /// A 2x1 grid with a start in the bottom left and an exit in the top right.
+    fn make_grid() -> Grid {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid
+    }
+
+    #[test]
+    fn validate_path_shape() {
+        let grid = make_grid();
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 4, 5, 2])),
+            Ok(())
+        );
+        assert_eq!(
+            grid.validate(&Path::new()),
+            Err(ValidationError::InvalidPath(PathError::Empty))
+        );
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 4, 5])),
+            Err(ValidationError::InvalidPath(PathError::DoesNotEndAtExit(5)))
+        );
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 5, 2])),
+            Err(ValidationError::InvalidPath(PathError::NotConnected(3, 5)))
+        );
+    }
+
+    #[test]
+    fn validate_squares() {
+        let mut grid = make_grid();
+        grid.set_cell_type(0, CellType::Square(Color::Black));
+        grid.set_cell_type(1, CellType::Square(Color::White));
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 4, 1, 2])),
+            Ok(())
+        );
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 4, 5, 2])),
+            Err(ValidationError::FailingSymbols(vec![
+                FailingSymbol::Cell(0),
+                FailingSymbol::Cell(1)
+            ]))
+        );
+    }
+
+    #[test]
+    fn validate_triangles_and_dots() {
+        let mut grid = make_grid();
+        grid.set_cell_type(0, CellType::Triangle(TriangleCount::Three));
+        grid.set_vertex_type(5, Vertex::Dot);
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 4, 1, 2])),
+            Err(ValidationError::FailingSymbols(vec![
+                FailingSymbol::VertexDot(5),
+                FailingSymbol::Cell(0)
+            ]))
+        );
+        assert!(grid
+            .validate(&Path::from_vertices(vec![3, 4, 5, 2]))
+            .is_err());
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 0, 1, 4, 5, 2])),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_polyominoes() {
+        let mut grid = make_grid();
+        let domino = Polyomino::from_mask(0b11, false).unwrap();
+        grid.set_cell_type(0, CellType::Polyomino(domino));
+        // Splitting the two cells leaves the domino without room
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 4, 1, 2])),
+            Err(ValidationError::FailingSymbols(vec![FailingSymbol::Cell(
+                0
+            )]))
+        );
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 0, 1, 2])),
+            Ok(())
+        );
+        // A standing domino doesn't fit a lying region unless it can be turned
+        let standing = Polyomino::from_mask(0b10001, false).unwrap();
+        grid.set_cell_type(0, CellType::Polyomino(standing));
+        assert!(grid
+            .validate(&Path::from_vertices(vec![3, 0, 1, 2]))
+            .is_err());
+        let standing = Polyomino::from_mask(0b10001, true).unwrap();
+        grid.set_cell_type(0, CellType::Polyomino(standing));
+        assert_eq!(
+            grid.validate(&Path::from_vertices(vec![3, 0, 1, 2])),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,619 @@
+//! Decoding and encoding The Windmill's share codes.
+//!
+//! A share code is a puzzle stored as a protobuf `Storage` message, written out in base64.
+//! The message holds the width of the puzzle's storage grid and one `Entity` for every spot in it, row by row.
+//! A puzzle that's `w` cells wide and `h` cells tall is stored in a grid that's `2w + 1` wide and `2h + 1` tall,
+//! where spots with both coordinates even are vertices, spots with both odd are cells, and the rest are edges.
+//!
+//! Everything is done here, without any network access or outside crates, so codes work fully offline.
+//! Puzzles with symmetry, pillars, eliminators, negative polyominoes, colored dots or any other unknown entity
+//! can't be decoded, and fail with `ShareCodeError::Unsupported`.
+
+use std::fmt;
+
+use crate::grid::{
+    polyomino::POLYOMINO_MAX_SIZE, CellType, Color, EdgeType, Grid, GridVector2, Polyomino,
+    TriangleCount, Vertex,
+};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Field numbers of the messages in a share code
+const STORAGE_WIDTH: u32 = 2;
+const STORAGE_ENTITY: u32 = 3;
+const STORAGE_SYMMETRY: u32 = 4;
+const STORAGE_PILLAR: u32 = 5;
+const ENTITY_TYPE: u32 = 1;
+const ENTITY_COLOR: u32 = 2;
+const ENTITY_SHAPE: u32 = 3;
+const ENTITY_COUNT: u32 = 4;
+const ENTITY_ORIENTATION: u32 = 5;
+const SHAPE_GRID: u32 = 1;
+const SHAPE_WIDTH: u32 = 2;
+const SHAPE_FREE: u32 = 3;
+const SHAPE_NEGATIVE: u32 = 4;
+const ORIENTATION_HORIZONTAL: u32 = 1;
+const ORIENTATION_VERTICAL: u32 = 2;
+
+// Values of the `Type` enum, named like in The Windmill's `grid.proto`, where gaps are disjoint edges and dots are hexagons
+const TYPE_NONE: u64 = 0;
+const TYPE_BASIC: u64 = 1;
+const TYPE_START: u64 = 2;
+const TYPE_END: u64 = 3;
+const TYPE_DISJOINT: u64 = 4;
+const TYPE_HEXAGON: u64 = 5;
+const TYPE_SQUARE: u64 = 6;
+const TYPE_STAR: u64 = 7;
+const TYPE_TETRIS: u64 = 8;
+const TYPE_ERROR: u64 = 9;
+const TYPE_TRIANGLE: u64 = 10;
+
+/// The values of the `Color` enum, which starts at 1 since 0 means no color was set.
+const COLORS: [Color; 9] = [
+    Color::Black,
+    Color::White,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Orange,
+];
+
+// Values of the enums in `Orientation`
+const LEFT: u64 = 1;
+const RIGHT: u64 = 2;
+const TOP: u64 = 1;
+const BOTTOM: u64 = 2;
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_BYTES: u64 = 2;
+const WIRE_FIXED32: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareCodeError {
+    /// The code has a character that isn't part of base64, at this index.
+    InvalidCharacter(usize),
+    /// The decoded code isn't a valid puzzle.
+    Malformed(String),
+    /// The puzzle uses something our puzzles don't have.
+    Unsupported(String),
+}
+
+impl fmt::Display for ShareCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareCodeError::InvalidCharacter(index) => {
+                write!(f, "the code has an invalid character at {index}")
+            }
+            ShareCodeError::Malformed(reason) => write!(f, "malformed share code: {reason}"),
+            ShareCodeError::Unsupported(feature) => write!(f, "{feature} aren't supported"),
+        }
+    }
+}
+
+impl std::error::Error for ShareCodeError {}
+
+fn malformed(reason: impl Into<String>) -> ShareCodeError {
+    ShareCodeError::Malformed(reason.into())
+}
+
+/// Decodes base64, accepting both the standard and the URL-safe alphabet, with or without padding.
+/// Whitespace is skipped, since codes are often pasted with a line break at the end.
+fn decode_base64(code: &str) -> Result<Vec<u8>, ShareCodeError> {
+    let mut bytes = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for (index, character) in code.bytes().enumerate() {
+        let value = match character {
+            b'-' => 62,
+            b'_' => 63,
+            b'=' => break,
+            character if character.is_ascii_whitespace() => continue,
+            character => BASE64_ALPHABET
+                .iter()
+                .position(|&other| other == character)
+                .ok_or(ShareCodeError::InvalidCharacter(index))? as u32,
+        };
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes)
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut code = String::new();
+    for chunk in bytes.chunks(3) {
+        let buffer = chunk.iter().enumerate().fold(0u32, |buffer, (i, &byte)| {
+            buffer | (byte as u32) << (16 - 8 * i)
+        });
+        (0..4).for_each(|i| {
+            code.push(if i <= chunk.len() {
+                BASE64_ALPHABET[(buffer >> (18 - 6 * i) & 0x3f) as usize] as char
+            } else {
+                '='
+            })
+        });
+    }
+    code
+}
+
+/// A field read out of a protobuf message.
+enum FieldValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Reads the fields of a protobuf message one by one.
+struct MessageReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MessageReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        MessageReader { bytes }
+    }
+
+    fn read_varint(&mut self) -> Result<u64, ShareCodeError> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self
+                .bytes
+                .split_first()
+                .ok_or_else(|| malformed("a number runs past the end"))?;
+            self.bytes = rest;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(malformed("a number is too long"))
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ShareCodeError> {
+        if length > self.bytes.len() {
+            return Err(malformed("a field runs past the end"));
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads the next field that's a number or bytes, skipping any fixed size fields, which share codes don't use.
+    fn next_field(&mut self) -> Result<Option<(u32, FieldValue<'a>)>, ShareCodeError> {
+        while !self.bytes.is_empty() {
+            let key = self.read_varint()?;
+            let field = (key >> 3) as u32;
+            match key & 7 {
+                WIRE_VARINT => return Ok(Some((field, FieldValue::Varint(self.read_varint()?)))),
+                WIRE_BYTES => {
+                    let length = self.read_varint()? as usize;
+                    return Ok(Some((field, FieldValue::Bytes(self.take(length)?))));
+                }
+                WIRE_FIXED64 => _ = self.take(8)?,
+                WIRE_FIXED32 => _ = self.take(4)?,
+                wire_type => return Err(malformed(format!("unknown wire type {wire_type}"))),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Writes the fields of a protobuf message. Fields with default values are left out, like protobuf does.
+#[derive(Default)]
+struct MessageWriter {
+    bytes: Vec<u8>,
+}
+
+impl MessageWriter {
+    fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    fn varint(&mut self, field: u32, value: u64) {
+        if value != 0 {
+            self.write_varint((field as u64) << 3 | WIRE_VARINT);
+            self.write_varint(value);
+        }
+    }
+
+    fn bytes(&mut self, field: u32, bytes: &[u8]) {
+        self.write_varint((field as u64) << 3 | WIRE_BYTES);
+        self.write_varint(bytes.len() as u64);
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    fn message(&mut self, field: u32, message: MessageWriter) {
+        self.bytes(field, &message.bytes);
+    }
+}
+
+/// The parts of an `Entity` that our puzzles can use.
+#[derive(Default)]
+struct Entity {
+    kind: u64,
+    color: u64,
+    count: u64,
+    /// The squares of a polyomino, row by row, and how wide its rows are.
+    shape: Vec<bool>,
+    shape_width: usize,
+    free: bool,
+    negative: bool,
+    orientation: (u64, u64),
+}
+
+impl Entity {
+    fn read(bytes: &[u8]) -> Result<Self, ShareCodeError> {
+        let mut entity = Entity::default();
+        let mut reader = MessageReader::new(bytes);
+        while let Some((field, value)) = reader.next_field()? {
+            match (field, value) {
+                (ENTITY_TYPE, FieldValue::Varint(kind)) => entity.kind = kind,
+                (ENTITY_COLOR, FieldValue::Varint(color)) => entity.color = color,
+                (ENTITY_COUNT, FieldValue::Varint(count)) => entity.count = count,
+                (ENTITY_SHAPE, FieldValue::Bytes(shape)) => entity.read_shape(shape)?,
+                (ENTITY_ORIENTATION, FieldValue::Bytes(orientation)) => {
+                    let mut reader = MessageReader::new(orientation);
+                    while let Some((field, value)) = reader.next_field()? {
+                        match (field, value) {
+                            (ORIENTATION_HORIZONTAL, FieldValue::Varint(side)) => {
+                                entity.orientation.0 = side
+                            }
+                            (ORIENTATION_VERTICAL, FieldValue::Varint(side)) => {
+                                entity.orientation.1 = side
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(entity)
+    }
+
+    fn read_shape(&mut self, bytes: &[u8]) -> Result<(), ShareCodeError> {
+        let mut reader = MessageReader::new(bytes);
+        while let Some((field, value)) = reader.next_field()? {
+            match (field, value) {
+                // Repeated booleans are usually packed together, but may also come one at a time
+                (SHAPE_GRID, FieldValue::Bytes(packed)) => {
+                    self.shape.extend(packed.iter().map(|&square| square != 0))
+                }
+                (SHAPE_GRID, FieldValue::Varint(square)) => self.shape.push(square != 0),
+                (SHAPE_WIDTH, FieldValue::Varint(width)) => self.shape_width = width as usize,
+                (SHAPE_FREE, FieldValue::Varint(free)) => self.free = free != 0,
+                (SHAPE_NEGATIVE, FieldValue::Varint(negative)) => self.negative = negative != 0,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&self) -> MessageWriter {
+        let mut message = MessageWriter::default();
+        message.varint(ENTITY_TYPE, self.kind);
+        message.varint(ENTITY_COLOR, self.color);
+        if !self.shape.is_empty() {
+            let mut shape = MessageWriter::default();
+            let packed: Vec<u8> = self.shape.iter().map(|&square| square as u8).collect();
+            shape.bytes(SHAPE_GRID, &packed);
+            shape.varint(SHAPE_WIDTH, self.shape_width as u64);
+            shape.varint(SHAPE_FREE, self.free as u64);
+            message.message(ENTITY_SHAPE, shape);
+        }
+        message.varint(ENTITY_COUNT, self.count);
+        if self.orientation != (0, 0) {
+            let mut orientation = MessageWriter::default();
+            orientation.varint(ORIENTATION_HORIZONTAL, self.orientation.0);
+            orientation.varint(ORIENTATION_VERTICAL, self.orientation.1);
+            message.message(ENTITY_ORIENTATION, orientation);
+        }
+        message
+    }
+
+    fn get_color(&self) -> Result<Color, ShareCodeError> {
+        match self.color {
+            // Symbols without a color are drawn in black
+            0 => Ok(Color::Black),
+            color => COLORS
+                .get(color as usize - 1)
+                .copied()
+                .ok_or_else(|| ShareCodeError::Unsupported(format!("symbols of color {color}"))),
+        }
+    }
+}
+
+/// Decodes a share code into a puzzle.
+pub fn decode(code: &str) -> Result<Grid, ShareCodeError> {
+    let bytes = decode_base64(code)?;
+    let mut width = 0;
+    let mut entities = vec![];
+    let mut reader = MessageReader::new(&bytes);
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (STORAGE_WIDTH, FieldValue::Varint(value)) => width = value as usize,
+            (STORAGE_ENTITY, FieldValue::Bytes(entity)) => entities.push(Entity::read(entity)?),
+            // Symmetry and pillars are refused however they're stored, rather than silently dropped
+            (STORAGE_SYMMETRY, FieldValue::Varint(1..)) => {
+                return Err(ShareCodeError::Unsupported("symmetry puzzles".into()))
+            }
+            (STORAGE_SYMMETRY, FieldValue::Bytes([_, ..])) => {
+                return Err(ShareCodeError::Unsupported("symmetry puzzles".into()))
+            }
+            (STORAGE_PILLAR, FieldValue::Varint(1..)) => {
+                return Err(ShareCodeError::Unsupported("pillars".into()))
+            }
+            _ => {}
+        }
+    }
+    if width < 3 || width % 2 == 0 || entities.len() % width != 0 {
+        return Err(malformed(format!(
+            "{} entities can't be laid out {width} wide",
+            entities.len()
+        )));
+    }
+    let height = entities.len() / width;
+    if height < 3 || height % 2 == 0 {
+        return Err(malformed(format!("the puzzle is {height} entities tall")));
+    }
+
+    let mut grid = Grid::new(vec2!(width / 2, height / 2));
+    for (index, entity) in entities.iter().enumerate() {
+        let (x, y) = (index % width, index / width);
+        match (x % 2, y % 2) {
+            (0, 0) => {
+                let kind = match entity.kind {
+                    TYPE_NONE => Vertex::None,
+                    TYPE_BASIC => Vertex::Vertex,
+                    TYPE_START => Vertex::Start,
+                    TYPE_END => Vertex::Exit,
+                    TYPE_HEXAGON if entity.color <= 1 => Vertex::Dot,
+                    TYPE_HEXAGON => return Err(ShareCodeError::Unsupported("colored dots".into())),
+                    kind => return Err(malformed(format!("a vertex has type {kind}"))),
+                };
+                grid.set_vertex_type(grid.get_vertex_id_at(vec2!(x, y) / 2).unwrap(), kind);
+            }
+            (1, 1) => {
+                let kind = match entity.kind {
+                    TYPE_NONE | TYPE_BASIC => CellType::Blank,
+                    TYPE_SQUARE => CellType::Square(entity.get_color()?),
+                    TYPE_STAR => CellType::Star(entity.get_color()?),
+                    TYPE_TRIANGLE => CellType::Triangle(
+                        u8::try_from(entity.count)
+                            .ok()
+                            .and_then(|count| TriangleCount::try_from(count).ok())
+                            .ok_or_else(|| malformed("triangles need a count from 1 to 3"))?,
+                    ),
+                    TYPE_TETRIS if entity.negative => {
+                        return Err(ShareCodeError::Unsupported("negative polyominoes".into()))
+                    }
+                    TYPE_TETRIS if entity.shape_width == 0 => {
+                        return Err(malformed("a polyomino has no width"))
+                    }
+                    TYPE_TETRIS => {
+                        let squares: Vec<GridVector2> = entity
+                            .shape
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, &square)| square)
+                            .map(|(i, _)| vec2!(i % entity.shape_width, i / entity.shape_width))
+                            .collect();
+                        CellType::Polyomino(
+                            Polyomino::new(&squares, entity.free).ok_or_else(|| {
+                                malformed(format!("polyominoes need 1 to {POLYOMINO_MAX_SIZE}x{POLYOMINO_MAX_SIZE} squares"))
+                            })?,
+                        )
+                    }
+                    TYPE_ERROR => return Err(ShareCodeError::Unsupported("eliminators".into())),
+                    kind => {
+                        return Err(ShareCodeError::Unsupported(format!(
+                            "symbols of type {kind}"
+                        )))
+                    }
+                };
+                grid.set_cell_type(grid.get_cell_id_at(vec2!(x, y) / 2).unwrap(), kind);
+            }
+            _ => {
+                let kind = match entity.kind {
+                    TYPE_BASIC => EdgeType::Edge,
+                    // An edge that's missing entirely can't be crossed either
+                    TYPE_NONE | TYPE_DISJOINT => EdgeType::Gap,
+                    TYPE_HEXAGON if entity.color <= 1 => EdgeType::Dot,
+                    TYPE_HEXAGON => return Err(ShareCodeError::Unsupported("colored dots".into())),
+                    kind => return Err(malformed(format!("an edge has type {kind}"))),
+                };
+                let a = grid.get_vertex_id_at(vec2!(x / 2, y / 2)).unwrap();
+                let b = grid
+                    .get_vertex_id_at(vec2!(x.div_ceil(2), y.div_ceil(2)))
+                    .unwrap();
+                grid.set_edge_type(grid.get_edge_id_between(a, b).unwrap(), kind);
+            }
+        }
+    }
+    Ok(grid)
+}
+
+/// Encodes a puzzle as a share code.
+pub fn encode(grid: &Grid) -> String {
+    let size = grid.get_size();
+    let width = 2 * size.x + 1;
+    let mut storage = MessageWriter::default();
+    storage.varint(STORAGE_WIDTH, width as u64);
+    for y in 0..=2 * size.y {
+        for x in 0..width {
+            let mut entity = Entity::default();
+            match (x % 2, y % 2) {
+                (0, 0) => {
+                    let vertex = vec2!(x, y) / 2;
+                    entity.kind = match grid
+                        .get_vertex_type(grid.get_vertex_id_at(vertex).unwrap())
+                        .unwrap()
+                    {
+                        Vertex::None => TYPE_NONE,
+                        Vertex::Vertex => TYPE_BASIC,
+                        Vertex::Start => TYPE_START,
+                        Vertex::Dot => TYPE_HEXAGON,
+                        Vertex::Exit => {
+                            // Point the nub out of the grid, the same way it's drawn
+                            entity.orientation = if vertex.x == 0 {
+                                (LEFT, 0)
+                            } else if vertex.x == size.x {
+                                (RIGHT, 0)
+                            } else if vertex.y == 0 {
+                                (0, TOP)
+                            } else if vertex.y == size.y {
+                                (0, BOTTOM)
+                            } else {
+                                (0, 0)
+                            };
+                            TYPE_END
+                        }
+                    };
+                }
+                (1, 1) => {
+                    let color_value = |color: Color| {
+                        COLORS.iter().position(|&other| other == color).unwrap() as u64 + 1
+                    };
+                    match grid
+                        .get_cell_type(grid.get_cell_id_at(vec2!(x, y) / 2).unwrap())
+                        .unwrap()
+                    {
+                        CellType::Blank => entity.kind = TYPE_NONE,
+                        CellType::Square(color) => {
+                            entity.kind = TYPE_SQUARE;
+                            entity.color = color_value(color);
+                        }
+                        CellType::Star(color) => {
+                            entity.kind = TYPE_STAR;
+                            entity.color = color_value(color);
+                        }
+                        CellType::Triangle(count) => {
+                            entity.kind = TYPE_TRIANGLE;
+                            entity.color = color_value(Color::Orange);
+                            entity.count = count as u64;
+                        }
+                        CellType::Polyomino(polyomino) => {
+                            let squares = polyomino.get_squares();
+                            entity.kind = TYPE_TETRIS;
+                            entity.color = color_value(Color::Yellow);
+                            entity.shape_width = polyomino.get_size().x;
+                            entity.shape = (0..polyomino.get_size().y)
+                                .flat_map(|y| (0..entity.shape_width).map(move |x| vec2!(x, y)))
+                                .map(|square| squares.contains(&square))
+                                .collect();
+                            entity.free = polyomino.is_rotatable();
+                        }
+                    }
+                }
+                _ => {
+                    let a = grid.get_vertex_id_at(vec2!(x / 2, y / 2)).unwrap();
+                    let b = grid
+                        .get_vertex_id_at(vec2!(x.div_ceil(2), y.div_ceil(2)))
+                        .unwrap();
+                    entity.kind = match grid
+                        .get_edge_type(grid.get_edge_id_between(a, b).unwrap())
+                        .unwrap()
+                    {
+                        EdgeType::Edge => TYPE_BASIC,
+                        EdgeType::Gap => TYPE_DISJOINT,
+                        EdgeType::Dot => TYPE_HEXAGON,
+                    };
+                }
+            }
+            storage.message(STORAGE_ENTITY, entity.write());
+        }
+    }
+    encode_base64(&storage.bytes)
+}
+
+impl Grid {
+    /// Decodes a puzzle from one of The Windmill's share codes.
+    pub fn from_share_code(code: &str) -> Result<Grid, ShareCodeError> {
+        decode(code)
+    }
+
+    /// Encodes this puzzle as one of The Windmill's share codes.
+    pub fn to_share_code(&self) -> String {
+        encode(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64() {
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(encode_base64(&[0xfb, 0xff]), "+/8=");
+        assert_eq!(decode_base64("TWE=").unwrap(), b"Ma");
+        assert_eq!(decode_base64("-_8\n").unwrap(), vec![0xfb, 0xff]);
+        assert_eq!(
+            decode_base64("TW!u"),
+            Err(ShareCodeError::InvalidCharacter(2))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_vertex_type(1, Vertex::Dot);
+        grid.set_vertex_type(5, Vertex::None);
+        grid.set_edge_type(grid.get_edge_id_between(0, 1).unwrap(), EdgeType::Gap);
+        grid.set_cell_type(0, CellType::Star(Color::Magenta));
+        let l = Polyomino::new(&[vec2!(0, 0), vec2!(0, 1), vec2!(1, 1)], true).unwrap();
+        grid.set_cell_type(1, CellType::Polyomino(l));
+
+        let code = grid.to_share_code();
+        let decoded = Grid::from_share_code(&code).unwrap();
+        assert_eq!(decoded.to_text(), grid.to_text());
+        assert_eq!(decoded.to_share_code(), code);
+    }
+
+    #[test]
+    fn decodes_hand_written_codes() {
+        // A 1x1 puzzle, written out byte by byte with the field numbers and values from `grid.proto`:
+        // width 3 (10 03), then nine entities (1a, length, fields) row by row:
+        // basic (08 01), disjoint (08 04), end pointing right (08 03 2a 02 08 02),
+        // hexagon (08 05), cyan star (08 07 10 03), basic,
+        // start (08 02), basic, basic
+        let code = "EAMaAggBGgIIBBoGCAMqAggCGgIIBRoECAcQAxoCCAEaAggCGgIIARoCCAE=";
+        let grid = decode(code).unwrap();
+        assert_eq!(grid.get_vertex_type(2), Some(Vertex::Start));
+        assert_eq!(grid.get_vertex_type(1), Some(Vertex::Exit));
+        assert_eq!(
+            grid.get_edge_type(grid.get_edge_id_between(0, 1).unwrap()),
+            Some(EdgeType::Gap)
+        );
+        assert_eq!(
+            grid.get_edge_type(grid.get_edge_id_between(0, 2).unwrap()),
+            Some(EdgeType::Dot)
+        );
+        assert_eq!(grid.get_cell_type(0), Some(CellType::Star(Color::Cyan)));
+        assert_eq!(grid.to_share_code(), code);
+
+        // The same puzzle with horizontal symmetry (20 01)
+        let symmetric = "EAMaAggBGgIIBBoGCAMqAggCGgIIBRoECAcQAxoCCAEaAggCGgIIARoCCAEgAQ==";
+        assert_eq!(
+            decode(symmetric).unwrap_err(),
+            ShareCodeError::Unsupported("symmetry puzzles".into())
+        );
+        assert!(matches!(decode("CAk"), Err(ShareCodeError::Malformed(_))));
+    }
+}
@@ -0,0 +1,446 @@
+//! Importing and exporting puzzles in the JSON format used by the witnesspuzzles.com web editor.
+//! This needs the `witnesspuzzles` feature.
+//!
+//! That format stores a puzzle as a `grid` of columns, where every column holds every vertex, edge and cell in it
+//! from top to bottom. A puzzle that's `w` cells wide and `h` cells tall has `2w + 1` columns of `2h + 1` entries.
+//! Entries where both coordinates are even are vertices, entries where both are odd are cells, and the rest are edges.
+//! Vertices and edges are objects with `"type": "line"`, and can have a `dot`, a `gap`, `"start": true`, or an `end`
+//! naming the side its nub points to. Cells are `null` when they're empty, or an object with the `type` of the symbol
+//! along with its `color`, the `count` of a triangle, or the `polyshape` of a polyomino.
+//!
+//! Not everything in that format exists in our puzzles. Importing a puzzle that uses eliminators, negative polyominoes,
+//! symmetry, pillars, colored or invisible dots, or any other unknown symbol fails with
+//! `WitnessPuzzlesError::Unsupported` naming what isn't supported.
+//! Polyominoes have no color in our puzzles, so the colors of imported polyominoes are dropped.
+
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::grid::{CellType, Color, EdgeType, Grid, GridVector2, Polyomino, TriangleCount, Vertex};
+
+/// The dot that's drawn in black, as opposed to the colored dots of symmetry puzzles.
+const DOT_BLACK: u64 = 1;
+/// The bit of a polyshape that's set when the polyomino can be rotated.
+const ROTATION_BIT: u64 = 1 << 20;
+/// The color polyominoes are drawn in when they're exported, since ours don't have one.
+const POLYOMINO_COLOR: &str = "yellow";
+const COLORS: [(&str, Color); 9] = [
+    ("black", Color::Black),
+    ("white", Color::White),
+    ("cyan", Color::Cyan),
+    ("magenta", Color::Magenta),
+    ("yellow", Color::Yellow),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("blue", Color::Blue),
+    ("orange", Color::Orange),
+];
+
+#[derive(Debug)]
+pub enum WitnessPuzzlesError {
+    /// The text isn't JSON.
+    Json(serde_json::Error),
+    /// The JSON isn't shaped like a puzzle.
+    Malformed(String),
+    /// The puzzle uses something that can't be represented in the other format.
+    /// `position` is where it is in the witnesspuzzles.com grid, if it's a single spot.
+    Unsupported {
+        feature: String,
+        position: Option<GridVector2>,
+    },
+}
+
+impl fmt::Display for WitnessPuzzlesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessPuzzlesError::Json(error) => write!(f, "invalid JSON: {error}"),
+            WitnessPuzzlesError::Malformed(reason) => write!(f, "malformed puzzle: {reason}"),
+            WitnessPuzzlesError::Unsupported {
+                feature,
+                position: Some(position),
+            } => write!(
+                f,
+                "{feature} at ({}, {}) aren't supported",
+                position.x, position.y
+            ),
+            WitnessPuzzlesError::Unsupported {
+                feature,
+                position: None,
+            } => write!(f, "{feature} aren't supported"),
+        }
+    }
+}
+
+impl std::error::Error for WitnessPuzzlesError {}
+
+impl From<serde_json::Error> for WitnessPuzzlesError {
+    fn from(error: serde_json::Error) -> Self {
+        WitnessPuzzlesError::Json(error)
+    }
+}
+
+fn unsupported(feature: impl Into<String>, position: Option<GridVector2>) -> WitnessPuzzlesError {
+    WitnessPuzzlesError::Unsupported {
+        feature: feature.into(),
+        position,
+    }
+}
+
+fn malformed(reason: impl Into<String>) -> WitnessPuzzlesError {
+    WitnessPuzzlesError::Malformed(reason.into())
+}
+
+/// Reads a puzzle from witnesspuzzles.com JSON.
+pub fn import(json: &str) -> Result<Grid, WitnessPuzzlesError> {
+    let root: Value = serde_json::from_str(json)?;
+    let root = root
+        .as_object()
+        .ok_or_else(|| malformed("the puzzle isn't an object"))?;
+    if root.get("pillar").and_then(Value::as_bool) == Some(true) {
+        return Err(unsupported("pillars", None));
+    }
+    if let Some(symmetry) = root.get("symmetry").and_then(Value::as_object) {
+        if symmetry.values().any(|axis| axis.as_bool() == Some(true)) {
+            return Err(unsupported("symmetry puzzles", None));
+        }
+    }
+    let columns = root
+        .get("grid")
+        .and_then(Value::as_array)
+        .ok_or_else(|| malformed("the puzzle has no grid"))?;
+    let height = columns
+        .first()
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len);
+    if columns.len() < 3 || columns.len() % 2 == 0 || height < 3 || height % 2 == 0 {
+        return Err(malformed(format!(
+            "a {}x{} grid doesn't have an odd size of at least 3x3",
+            columns.len(),
+            height
+        )));
+    }
+    let mut grid = Grid::new(vec2!(columns.len() / 2, height / 2));
+    for (x, column) in columns.iter().enumerate() {
+        let column = column
+            .as_array()
+            .filter(|column| column.len() == height)
+            .ok_or_else(|| malformed(format!("column {x} isn't {height} entries long")))?;
+        for (y, entry) in column.iter().enumerate() {
+            let position = vec2!(x, y);
+            let entry = match entry {
+                Value::Null => None,
+                Value::Object(entry) => Some(entry),
+                _ => {
+                    return Err(malformed(format!(
+                        "the entry at ({x}, {y}) isn't an object or null"
+                    )))
+                }
+            };
+            match (x % 2, y % 2) {
+                (0, 0) => {
+                    let id = grid.get_vertex_id_at(position / 2).unwrap();
+                    grid.set_vertex_type(id, import_vertex(entry, position)?);
+                }
+                (1, 1) => {
+                    let id = grid.get_cell_id_at(position / 2).unwrap();
+                    grid.set_cell_type(id, import_cell(entry, position)?);
+                }
+                _ => {
+                    // Edges sit halfway between the two vertices they connect
+                    let a = grid.get_vertex_id_at(vec2!(x / 2, y / 2)).unwrap();
+                    let b = grid
+                        .get_vertex_id_at(vec2!(x.div_ceil(2), y.div_ceil(2)))
+                        .unwrap();
+                    let id = grid.get_edge_id_between(a, b).unwrap();
+                    grid.set_edge_type(id, import_edge(entry, position)?);
+                }
+            }
+        }
+    }
+    Ok(grid)
+}
+
+/// Reads the dot of a vertex or edge, if it has one.
+fn import_dot(
+    entry: &Map<String, Value>,
+    position: GridVector2,
+) -> Result<bool, WitnessPuzzlesError> {
+    match entry.get("dot").and_then(Value::as_u64) {
+        None | Some(0) => Ok(false),
+        Some(DOT_BLACK) => Ok(true),
+        Some(_) => Err(unsupported("colored and invisible dots", Some(position))),
+    }
+}
+
+fn import_vertex(
+    entry: Option<&Map<String, Value>>,
+    position: GridVector2,
+) -> Result<Vertex, WitnessPuzzlesError> {
+    let Some(entry) = entry else {
+        return Ok(Vertex::Vertex);
+    };
+    let start = entry.get("start").and_then(Value::as_bool) == Some(true);
+    let end = entry.get("end").is_some_and(|end| !end.is_null());
+    let dot = import_dot(entry, position)?;
+    match (start, end, dot) {
+        (false, false, false) => Ok(Vertex::Vertex),
+        (true, false, false) => Ok(Vertex::Start),
+        (false, true, false) => Ok(Vertex::Exit),
+        (false, false, true) => Ok(Vertex::Dot),
+        _ => Err(unsupported(
+            "vertices with more than one of a start, an exit and a dot",
+            Some(position),
+        )),
+    }
+}
+
+fn import_edge(
+    entry: Option<&Map<String, Value>>,
+    position: GridVector2,
+) -> Result<EdgeType, WitnessPuzzlesError> {
+    let Some(entry) = entry else {
+        return Ok(EdgeType::Edge);
+    };
+    if entry.get("start").and_then(Value::as_bool) == Some(true)
+        || entry.get("end").is_some_and(|end| !end.is_null())
+    {
+        return Err(unsupported(
+            "starts and exits in the middle of edges",
+            Some(position),
+        ));
+    }
+    // Both partial and full gaps stop the line from crossing
+    let gap = entry.get("gap").and_then(Value::as_u64).unwrap_or(0) != 0;
+    match (gap, import_dot(entry, position)?) {
+        (false, false) => Ok(EdgeType::Edge),
+        (true, false) => Ok(EdgeType::Gap),
+        (false, true) => Ok(EdgeType::Dot),
+        (true, true) => Err(malformed(format!(
+            "the edge at ({}, {}) has both a gap and a dot",
+            position.x, position.y
+        ))),
+    }
+}
+
+fn import_cell(
+    entry: Option<&Map<String, Value>>,
+    position: GridVector2,
+) -> Result<CellType, WitnessPuzzlesError> {
+    let Some(entry) = entry else {
+        return Ok(CellType::Blank);
+    };
+    let color = || {
+        let name = entry.get("color").and_then(Value::as_str).unwrap_or("");
+        COLORS
+            .iter()
+            .find(|(other, _)| *other == name)
+            .map(|(_, color)| *color)
+            .ok_or_else(|| unsupported(format!("symbols colored {name:?}"), Some(position)))
+    };
+    match entry.get("type").and_then(Value::as_str) {
+        None | Some("line") => Ok(CellType::Blank),
+        Some("square") => Ok(CellType::Square(color()?)),
+        Some("star") => Ok(CellType::Star(color()?)),
+        Some("triangle") => entry
+            .get("count")
+            .and_then(Value::as_u64)
+            .and_then(|count| TriangleCount::try_from(u8::try_from(count).ok()?).ok())
+            .map(CellType::Triangle)
+            .ok_or_else(|| {
+                malformed(format!(
+                    "the triangles at ({}, {}) don't have a count from 1 to 3",
+                    position.x, position.y
+                ))
+            }),
+        Some("poly") => {
+            let polyshape = entry.get("polyshape").and_then(Value::as_u64).unwrap_or(0);
+            // Bit `x * 4 + y` is set for every square the polyomino covers
+            let squares: Vec<GridVector2> = (0..16)
+                .filter(|bit| polyshape & (1 << bit) != 0)
+                .map(|bit| vec2!(bit / 4, bit % 4))
+                .collect();
+            Polyomino::new(&squares, polyshape & ROTATION_BIT != 0)
+                .map(CellType::Polyomino)
+                .ok_or_else(|| {
+                    malformed(format!(
+                        "the polyomino at ({}, {}) has no squares",
+                        position.x, position.y
+                    ))
+                })
+        }
+        Some("ylop") => Err(unsupported("negative polyominoes", Some(position))),
+        Some("nega") => Err(unsupported("eliminators", Some(position))),
+        Some(other) => Err(unsupported(format!("{other:?} symbols"), Some(position))),
+    }
+}
+
+/// Writes a puzzle as witnesspuzzles.com JSON.
+/// This fails if the puzzle has missing vertices or exits away from the border, which that format can't represent.
+pub fn export(grid: &Grid) -> Result<String, WitnessPuzzlesError> {
+    let size = grid.get_size();
+    let mut columns = vec![];
+    for x in 0..=2 * size.x {
+        let mut column = vec![];
+        for y in 0..=2 * size.y {
+            let position = vec2!(x, y);
+            column.push(match (x % 2, y % 2) {
+                (0, 0) => {
+                    let vertex = position / 2;
+                    let kind = grid
+                        .get_vertex_type(grid.get_vertex_id_at(vertex).unwrap())
+                        .unwrap();
+                    let mut entry = json!({ "type": "line", "line": 0 });
+                    match kind {
+                        Vertex::None => return Err(unsupported("missing vertices", Some(position))),
+                        Vertex::Vertex => {}
+                        Vertex::Dot => entry["dot"] = json!(DOT_BLACK),
+                        Vertex::Start => entry["start"] = json!(true),
+                        Vertex::Exit => {
+                            // The same direction the nub is drawn in
+                            entry["end"] = json!(if vertex.x == 0 {
+                                "left"
+                            } else if vertex.x == size.x {
+                                "right"
+                            } else if vertex.y == 0 {
+                                "top"
+                            } else if vertex.y == size.y {
+                                "bottom"
+                            } else {
+                                return Err(unsupported(
+                                    "exits away from the border",
+                                    Some(position),
+                                ));
+                            })
+                        }
+                    }
+                    entry
+                }
+                (1, 1) => {
+                    let kind = grid
+                        .get_cell_type(grid.get_cell_id_at(position / 2).unwrap())
+                        .unwrap();
+                    let color_name = |color: Color| {
+                        COLORS
+                            .iter()
+                            .find(|(_, other)| *other == color)
+                            .unwrap()
+                            .0
+                    };
+                    match kind {
+                        CellType::Blank => Value::Null,
+                        CellType::Square(color) => {
+                            json!({ "type": "square", "color": color_name(color) })
+                        }
+                        CellType::Star(color) => {
+                            json!({ "type": "star", "color": color_name(color) })
+                        }
+                        CellType::Triangle(count) => {
+                            json!({ "type": "triangle", "color": "orange", "count": count as u8 })
+                        }
+                        CellType::Polyomino(polyomino) => {
+                            let polyshape = polyomino
+                                .get_squares()
+                                .iter()
+                                .fold(0, |shape, square| shape | 1 << (square.x * 4 + square.y))
+                                | if polyomino.is_rotatable() {
+                                    ROTATION_BIT
+                                } else {
+                                    0
+                                };
+                            json!({ "type": "poly", "color": POLYOMINO_COLOR, "polyshape": polyshape })
+                        }
+                    }
+                }
+                _ => {
+                    let a = grid.get_vertex_id_at(vec2!(x / 2, y / 2)).unwrap();
+                    let b = grid.get_vertex_id_at(vec2!(x.div_ceil(2), y.div_ceil(2))).unwrap();
+                    let kind = grid
+                        .get_edge_type(grid.get_edge_id_between(a, b).unwrap())
+                        .unwrap();
+                    let mut entry = json!({ "type": "line", "line": 0 });
+                    match kind {
+                        EdgeType::Edge => {}
+                        EdgeType::Gap => entry["gap"] = json!(1),
+                        EdgeType::Dot => entry["dot"] = json!(DOT_BLACK),
+                    }
+                    entry
+                }
+            });
+        }
+        columns.push(Value::Array(column));
+    }
+    Ok(json!({
+        "width": 2 * size.x + 1,
+        "height": 2 * size.y + 1,
+        "pillar": false,
+        "grid": columns,
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x1 puzzle with a start in the bottom left, an exit on the right, a gap, a dot, a square and a rotatable L.
+    const PUZZLE: &str = r#"{
+        "width": 5, "height": 3, "pillar": false, "symmetry": {"x": false, "y": false},
+        "grid": [
+            [{"type": "line", "line": 0}, {"type": "line", "line": 0}, {"type": "line", "line": 0, "start": true}],
+            [{"type": "line", "line": 0, "gap": 1}, {"type": "square", "color": "white"}, {"type": "line", "line": 0}],
+            [{"type": "line", "line": 0, "dot": 1}, {"type": "line", "line": 0}, {"type": "line", "line": 0}],
+            [{"type": "line", "line": 0}, {"type": "poly", "color": "yellow", "polyshape": 1048595}, {"type": "line", "line": 0}],
+            [{"type": "line", "line": 0, "end": "right"}, {"type": "line", "line": 0}, {"type": "line", "line": 0}]
+        ]
+    }"#;
+
+    #[test]
+    fn import_and_export() {
+        let grid = import(PUZZLE).unwrap();
+        assert_eq!(grid.get_size(), vec2!(2, 1));
+        assert_eq!(grid.get_vertex_type(3), Some(Vertex::Start));
+        assert_eq!(grid.get_vertex_type(1), Some(Vertex::Dot));
+        assert_eq!(grid.get_vertex_type(2), Some(Vertex::Exit));
+        assert_eq!(
+            grid.get_edge_type(grid.get_edge_id_between(0, 1).unwrap()),
+            Some(EdgeType::Gap)
+        );
+        assert_eq!(grid.get_cell_type(0), Some(CellType::Square(Color::White)));
+        let Some(CellType::Polyomino(polyomino)) = grid.get_cell_type(1) else {
+            panic!("expected a polyomino");
+        };
+        assert!(polyomino.is_rotatable());
+        assert_eq!(polyomino.get_area(), 3);
+
+        let exported = export(&grid).unwrap();
+        let reimported = import(&exported).unwrap();
+        assert_eq!(export(&reimported).unwrap(), exported);
+        assert_eq!(reimported.to_text(), grid.to_text());
+    }
+
+    #[test]
+    fn unsupported_features() {
+        let eliminator = PUZZLE.replace(
+            r#"{"type": "square", "color": "white"}"#,
+            r#"{"type": "nega", "color": "white"}"#,
+        );
+        assert_eq!(
+            import(&eliminator).unwrap_err().to_string(),
+            "eliminators at (1, 1) aren't supported"
+        );
+        let symmetry = PUZZLE.replace(r#""x": false"#, r#""x": true"#);
+        assert!(matches!(
+            import(&symmetry),
+            Err(WitnessPuzzlesError::Unsupported { position: None, .. })
+        ));
+        assert!(matches!(import("{"), Err(WitnessPuzzlesError::Json(_))));
+
+        let mut grid = import(PUZZLE).unwrap();
+        grid.set_vertex_type(0, Vertex::None);
+        assert!(matches!(
+            export(&grid),
+            Err(WitnessPuzzlesError::Unsupported { position: Some(position), .. }) if position.x == 0 && position.y == 0
+        ));
+    }
+}
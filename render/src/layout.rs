@@ -0,0 +1,143 @@
+//! Fitting puzzles onto screens, and mapping between grid space and screen space.
+
+use gameplay::grid::{Grid, GridPoint};
+use witness_core::Vector2;
+
+use crate::{renderer::get_bounds, style::Style};
+
+/// A position on a screen, in pixels from its top left corner.
+pub type ScreenPoint = Vector2<f32>;
+
+/// The thinnest lines are drawn, in pixels, so small screens still show them.
+pub const MIN_LINE_WIDTH: f32 = 2.;
+
+/// A rectangle on a screen, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    pub position: ScreenPoint,
+    pub size: ScreenPoint,
+}
+
+impl ScreenRect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        ScreenRect {
+            position: vec2!(x, y),
+            size: vec2!(width, height),
+        }
+    }
+
+    /// Gets a rectangle covering a whole screen or window of the given size.
+    pub fn from_size(width: u32, height: u32) -> Self {
+        Self::new(0., 0., width as f32, height as f32)
+    }
+}
+
+/// The 3DS's top screen.
+pub const TOP_SCREEN: ScreenRect = ScreenRect {
+    position: Vector2 { x: 0., y: 0. },
+    size: Vector2 { x: 400., y: 240. },
+};
+/// The 3DS's bottom screen, which is also its touch screen.
+pub const BOTTOM_SCREEN: ScreenRect = ScreenRect {
+    position: Vector2 { x: 0., y: 0. },
+    size: Vector2 { x: 320., y: 240. },
+};
+
+/// Where a puzzle is on a screen, and how big it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// How many pixels one grid unit takes up.
+    scale: f32,
+    /// Where the grid's origin is on the screen.
+    offset: ScreenPoint,
+}
+
+impl Layout {
+    pub fn new(scale: f32, offset: ScreenPoint) -> Self {
+        Layout { scale, offset }
+    }
+
+    /// Lays a puzzle out as big as it fits in an area, background included, centered along whichever side has room left.
+    pub fn fit(grid: &Grid, style: &Style, area: ScreenRect) -> Self {
+        let (origin, bounds) = get_bounds(grid, style);
+        let scale = (area.size.x / bounds.x).min(area.size.y / bounds.y).max(0.);
+        let margin = (area.size - bounds * scale) / 2.;
+        Layout {
+            scale,
+            offset: area.position + margin - origin * scale,
+        }
+    }
+
+    pub fn get_scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub fn get_offset(&self) -> ScreenPoint {
+        self.offset
+    }
+
+    pub fn grid_to_screen(&self, point: GridPoint) -> ScreenPoint {
+        point * self.scale + self.offset
+    }
+
+    /// Maps a point on the screen back into grid space, e.g. to find what was clicked.
+    pub fn screen_to_grid(&self, point: ScreenPoint) -> GridPoint {
+        (point - self.offset) / self.scale
+    }
+
+    /// Maps a movement on the screen into grid space, where it isn't affected by the offset.
+    pub fn screen_delta_to_grid(&self, delta: ScreenPoint) -> GridPoint {
+        delta / self.scale
+    }
+
+    /// Gets a style with lines at least [`MIN_LINE_WIDTH`] pixels wide, so they don't vanish when puzzles are drawn small.
+    pub fn adjust_style(&self, style: &Style) -> Style {
+        Style {
+            line_width: style.line_width.max(MIN_LINE_WIDTH / self.scale),
+            ..*style
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_and_centers() {
+        let grid = Grid::new(vec2!(2, 2));
+        let style = Style::default();
+        // 3.5 grid units across with the default margins
+        let wide = Layout::fit(&grid, &style, TOP_SCREEN);
+        assert!((wide.get_scale() - 240. / 3.5).abs() < 1e-4);
+        let center = wide.grid_to_screen(vec2!(1., 1.));
+        assert!((center - vec2!(200., 120.)).mag() < 1e-3);
+
+        let tall = Layout::fit(&grid, &style, ScreenRect::new(10., 20., 100., 300.));
+        assert!((tall.get_scale() - 100. / 3.5).abs() < 1e-4);
+        assert!((tall.grid_to_screen(vec2!(-0.75, 1.)) - vec2!(10., 170.)).mag() < 1e-3);
+    }
+
+    #[test]
+    fn maps_both_ways() {
+        let grid = Grid::new(vec2!(3, 1));
+        let layout = Layout::fit(&grid, &Style::default(), BOTTOM_SCREEN);
+        let point: GridPoint = vec2!(1.25, 0.5);
+        assert!((layout.screen_to_grid(layout.grid_to_screen(point)) - point).mag() < 1e-4);
+        assert_eq!(
+            layout.screen_delta_to_grid(vec2!(layout.get_scale(), 0.)),
+            vec2!(1., 0.)
+        );
+    }
+
+    #[test]
+    fn keeps_lines_visible() {
+        let style = Style::default();
+        assert_eq!(Layout::new(100., vec2!(0., 0.)).adjust_style(&style), style);
+        let small = Layout::new(4., vec2!(0., 0.));
+        assert_eq!(
+            small.adjust_style(&style).line_width * small.get_scale(),
+            MIN_LINE_WIDTH
+        );
+    }
+}
@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate witness_core;
+
+pub mod layout;
+pub mod mesh;
+pub mod png;
+pub mod raster;
+pub mod recording;
+pub mod renderer;
+pub mod shapes;
+pub mod style;
+pub mod svg;
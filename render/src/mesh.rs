@@ -0,0 +1,242 @@
+//! Triangle meshes of puzzles, for frontends that draw with a GPU.
+//! A mesh is a plain list of triangles that can be uploaded as a vertex buffer as it is.
+
+use gameplay::grid::{CellID, Grid, GridPoint};
+
+use crate::{
+    renderer::{draw_puzzle, Overlay, Renderer},
+    shapes,
+    style::{Rgba, Style},
+};
+
+/// A corner of a triangle, laid out like a vertex buffer expects it.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshVertex {
+    /// The position in grid space. The depth is always 0, and triangles are meant to be drawn in order.
+    pub position: [f32; 3],
+    /// The color, from 0 to 1 per channel, alpha last.
+    pub color: [f32; 4],
+}
+
+/// How many floats every vertex is made of in [`Mesh::to_floats`].
+pub const FLOATS_PER_VERTEX: usize = 7;
+
+/// A list of triangles, every three vertices making up one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mesh {
+    pub vertices: Vec<MeshVertex>,
+}
+
+impl Mesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    pub fn add_triangle(&mut self, corners: [GridPoint; 3], color: Rgba) {
+        let color = [color.r, color.g, color.b, color.a].map(|channel| channel as f32 / 255.);
+        self.vertices.extend(corners.map(|corner| MeshVertex {
+            position: [corner.x, corner.y, 0.],
+            color,
+        }));
+    }
+
+    /// Moves every vertex from grid space into some other space, e.g. pixels on a screen.
+    pub fn transform(&mut self, scale: f32, offset: GridPoint) {
+        self.vertices.iter_mut().for_each(|vertex| {
+            vertex.position[0] = vertex.position[0] * scale + offset.x;
+            vertex.position[1] = vertex.position[1] * scale + offset.y;
+        });
+    }
+
+    /// Gets the vertices as one flat array of floats, a position followed by a color for every vertex.
+    pub fn to_floats(&self) -> Vec<f32> {
+        self.vertices
+            .iter()
+            .flat_map(|vertex| vertex.position.into_iter().chain(vertex.color))
+            .collect()
+    }
+}
+
+/// Splits a polygon into triangles, given as indices into its points.
+/// The polygon may be concave and wind either way, but its sides mustn't cross.
+pub fn triangulate(outline: &[GridPoint]) -> Vec<[usize; 3]> {
+    if outline.len() < 3 {
+        return vec![];
+    }
+    let winding: f32 = (0..outline.len())
+        .map(|i| outline[i].cross(outline[(i + 1) % outline.len()]))
+        .sum::<f32>()
+        .signum();
+    // Points on the sides count as inside, as cutting through them would leave part of the triangle outside
+    let is_inside = |point: GridPoint, corners: [GridPoint; 3]| {
+        let [a, b, c] = corners;
+        !corners.contains(&point)
+            && [(a, b), (b, c), (c, a)]
+                .iter()
+                .all(|&(from, to)| (to - from).cross(point - from) * winding >= 0.)
+    };
+
+    // Cut off one corner at a time, as long as it doesn't bend inwards and no other point is in the way
+    let mut remaining: Vec<usize> = (0..outline.len()).collect();
+    let mut triangles = Vec::with_capacity(outline.len() - 2);
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + count - 1) % count],
+                remaining[i],
+                remaining[(i + 1) % count],
+            ]
+        };
+        let ear = (0..count)
+            .find(|&i| {
+                let indices = corner(i);
+                let [a, b, c] = indices.map(|index| outline[index]);
+                (b - a).cross(c - b) * winding > 0.
+                    && remaining
+                        .iter()
+                        .filter(|index| !indices.contains(index))
+                        .all(|&index| !is_inside(outline[index], [a, b, c]))
+            })
+            // Only points in a straight line are left, which can be cut off anywhere
+            .unwrap_or(0);
+        triangles.push(corner(ear));
+        remaining.remove(ear);
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+impl Renderer for Mesh {
+    fn fill_polygon(&mut self, outline: &[GridPoint], color: Rgba) {
+        for triangle in triangulate(outline) {
+            self.add_triangle(triangle.map(|index| outline[index]), color);
+        }
+    }
+
+    fn fill_circle(&mut self, center: GridPoint, radius: f32, color: Rgba) {
+        let outline = shapes::circle(center, radius);
+        for i in 0..outline.len() {
+            self.add_triangle(
+                [center, outline[i], outline[(i + 1) % outline.len()]],
+                color,
+            );
+        }
+    }
+
+    /// Lines are made of a rectangle for every segment, and a circle on every point for the rounded joints.
+    /// Where they overlap, transparent lines are drawn more than once.
+    fn draw_line(&mut self, line: &[GridPoint], width: f32, color: Rgba) {
+        for segment in line.windows(2) {
+            let direction = segment[1] - segment[0];
+            if direction.mag() == 0. {
+                continue;
+            }
+            let side = direction.norm().perp() * (width / 2.);
+            let [a, b, c, d] = [
+                segment[0] + side,
+                segment[1] + side,
+                segment[1] - side,
+                segment[0] - side,
+            ];
+            self.add_triangle([a, b, c], color);
+            self.add_triangle([a, c, d], color);
+        }
+        for &point in line {
+            self.fill_circle(point, width / 2., color);
+        }
+    }
+
+    /// Text isn't part of meshes, frontends draw it with their own fonts.
+    fn draw_text(&mut self, _position: GridPoint, _size: f32, _text: &str, _color: Rgba) {}
+}
+
+/// Builds the mesh of a puzzle with an overlay on top of it.
+pub fn build_puzzle_mesh(grid: &Grid, overlay: &Overlay, style: &Style) -> Mesh {
+    let mut mesh = Mesh::new();
+    draw_puzzle(&mut mesh, grid, overlay, style);
+    mesh
+}
+
+/// Builds a mesh covering the given cells, e.g. to shade regions.
+/// Every cell `color` gives a color for is filled with it, following the cell's vertices.
+pub fn build_cell_mesh(grid: &Grid, color: impl Fn(CellID) -> Option<Rgba>) -> Mesh {
+    let mut mesh = Mesh::new();
+    for (id, cell) in grid.get_all_cellrefs_iter().enumerate() {
+        if let Some(color) = color(id) {
+            let outline: Vec<GridPoint> = cell
+                .read()
+                .unwrap()
+                .get_vertices_in_winding_order()
+                .into_iter()
+                .map(|vertex| vec2cast!(grid.get_vertex_position(vertex).unwrap(), f32))
+                .collect();
+            mesh.fill_polygon(&outline, color);
+        }
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds up the areas of the triangles, which should add up to the polygon's area.
+    fn area(outline: &[GridPoint], triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|index| outline[index]);
+                ((b - a).cross(c - a) / 2.).abs()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn triangulates_concave_polygons() {
+        // An L shape, wound both ways
+        let mut outline: Vec<GridPoint> = vec![
+            vec2!(0., 0.),
+            vec2!(2., 0.),
+            vec2!(2., 1.),
+            vec2!(1., 1.),
+            vec2!(1., 2.),
+            vec2!(0., 2.),
+        ];
+        for _ in 0..2 {
+            let triangles = triangulate(&outline);
+            assert_eq!(triangles.len(), 4);
+            assert!((area(&outline, &triangles) - 3.).abs() < 1e-5);
+            outline.reverse();
+        }
+        let star = shapes::star(vec2!(0., 0.), 1.);
+        assert_eq!(triangulate(&star).len(), 14);
+        assert!(triangulate(&star[..2]).is_empty());
+    }
+
+    #[test]
+    fn builds_meshes() {
+        let grid = Grid::new(vec2!(2, 1));
+        let cells = build_cell_mesh(&grid, |cell| (cell == 1).then_some(Rgba::rgb(255, 0, 0)));
+        assert_eq!(cells.triangle_count(), 2);
+        assert!(cells
+            .vertices
+            .iter()
+            .all(|vertex| vertex.position[0] >= 1. && vertex.color == [1., 0., 0., 1.]));
+        assert_eq!(cells.to_floats().len(), 6 * FLOATS_PER_VERTEX);
+
+        let mut mesh = build_puzzle_mesh(&grid, &Overlay::default(), &Style::default());
+        assert_eq!(mesh.vertices.len() % 3, 0);
+        let first = mesh.vertices[0].position;
+        mesh.transform(10., vec2!(5., 0.));
+        assert_eq!(
+            mesh.vertices[0].position,
+            [first[0] * 10. + 5., first[1] * 10., 0.]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! A minimal PNG encoder, so images can be saved without pulling in any dependencies.
+//! The image data is stored uncompressed, which keeps the encoder small at the cost of bigger files.
+//! Files written this way can be read back, but compressed PNG files from elsewhere can't.
+
+/// The bytes every PNG file starts with.
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// The most bytes an uncompressed deflate block can hold.
+const MAX_STORED_BLOCK: usize = 0xffff;
+
+/// Computes the CRC-32 every chunk ends with.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+/// Computes the Adler-32 checksum zlib streams end with.
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+/// Wraps data in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let length = block.len() as u16;
+        stream.push(last as u8);
+        stream.extend(length.to_le_bytes());
+        stream.extend((!length).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+/// Reads the data out of a zlib stream made of uncompressed deflate blocks, checking its checksum.
+fn unzlib_stored(stream: &[u8]) -> Option<Vec<u8>> {
+    let mut rest = stream.get(2..)?;
+    let mut data = vec![];
+    loop {
+        let (&flags, block) = rest.split_first()?;
+        // The block type is in the two bits after the one that marks the last block, and stored blocks are type 0
+        if flags & 0b110 != 0 {
+            return None;
+        }
+        let length = u16::from_le_bytes([*block.first()?, *block.get(1)?]) as usize;
+        data.extend(block.get(4..4 + length)?);
+        rest = &block[4 + length..];
+        if flags & 1 == 1 {
+            break;
+        }
+    }
+    (rest.get(..4)? == adler32(&data).to_be_bytes()).then_some(data)
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// Encodes an image as a PNG file.
+/// The pixels are 8-bit RGBA, row by row from the top left, and there must be exactly `width * height` of them.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    assert_eq!(rgba.len(), width as usize * height as usize * 4);
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bits per channel, RGBA, default compression, default filtering, no interlacing
+    header.extend([8, 6, 0, 0, 0]);
+
+    // Every row starts with the filter it uses, which is always none
+    let mut scanlines = Vec::with_capacity(rgba.len() + height as usize);
+    if width > 0 {
+        for row in rgba.chunks(width as usize * 4) {
+            scanlines.push(0);
+            scanlines.extend(row);
+        }
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Decodes a PNG file like the ones `encode_png` writes: 8-bit RGBA without interlacing,
+/// stored in uncompressed blocks, with no filtering.
+/// Returns the width, the height and the pixels, or `None` if the file isn't one of those.
+pub fn decode_png(png: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let mut rest = png.strip_prefix(&SIGNATURE)?;
+    let mut header = None;
+    let mut stream = vec![];
+    while !rest.is_empty() {
+        let length = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+        let data = rest.get(8..8 + length)?;
+        match &rest[4..8] {
+            b"IHDR" => header = Some(data),
+            b"IDAT" => stream.extend(data),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Chunks end with a CRC, which isn't checked since the zlib stream has its own checksum
+        rest = rest.get(8 + length + 4..)?;
+    }
+
+    let header = header?;
+    if header.get(8..13)? != [8, 6, 0, 0, 0] {
+        return None;
+    }
+    let width = u32::from_be_bytes(header[..4].try_into().ok()?);
+    let height = u32::from_be_bytes(header[4..8].try_into().ok()?);
+    let scanlines = unzlib_stored(&stream)?;
+    let row_length = width as usize * 4;
+    if width == 0 || scanlines.len() != (row_length + 1) * height as usize {
+        return None;
+    }
+    let mut rgba = Vec::with_capacity(row_length * height as usize);
+    for row in scanlines.chunks(row_length + 1) {
+        match row.split_first()? {
+            (0, pixels) => rgba.extend(pixels),
+            _ => return None,
+        }
+    }
+    Some((width, height, rgba))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn encodes_chunks() {
+        let png = encode_png(1, 1, &[255, 0, 0, 255]);
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(
+            &png[png.len() - 12..],
+            &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+        // One stored block holding the filter byte and the pixel
+        let idat = &png[33 + 8..];
+        assert_eq!(&idat[..7], &[0x78, 0x01, 1, 5, 0, 0xfa, 0xff]);
+        assert_eq!(&idat[7..12], &[0, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn decodes_what_it_encodes() {
+        let rgba: Vec<u8> = (0..2 * 3 * 4).map(|byte| byte * 10).collect();
+        assert_eq!(decode_png(&encode_png(2, 3, &rgba)), Some((2, 3, rgba)));
+        // Big enough to take more than one block
+        let rgba = vec![7; 200 * 100 * 4];
+        assert_eq!(
+            decode_png(&encode_png(200, 100, &rgba)),
+            Some((200, 100, rgba))
+        );
+
+        let mut png = encode_png(1, 1, &[255, 0, 0, 255]);
+        // Compressed blocks can't be read
+        png[33 + 8 + 2] = 0b011;
+        assert_eq!(decode_png(&png), None);
+        assert_eq!(decode_png(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn splits_big_images_into_blocks() {
+        let stream = zlib_stored(&vec![0; MAX_STORED_BLOCK + 1]);
+        assert_eq!(stream[2], 0);
+        assert_eq!(stream[2 + 5 + MAX_STORED_BLOCK], 1);
+        assert_eq!(stream.len(), 2 + 5 + MAX_STORED_BLOCK + 5 + 1 + 4);
+    }
+}
@@ -0,0 +1,351 @@
+//! Rendering puzzles into images on the CPU, for tests and thumbnails on machines without a GPU.
+
+use std::{fs, io};
+
+use witness_core::Round;
+
+use gameplay::{
+    grid::{Grid, GridPoint},
+    path::Path,
+    tracer::get_path_polyline,
+    validation::FailingSymbol,
+};
+
+use crate::{
+    png::{decode_png, encode_png},
+    renderer::{draw_puzzle, get_bounds, Overlay, Renderer},
+    shapes,
+    style::{Rgba, Style},
+};
+
+/// How many rows each row of pixels is split into when measuring how much of a pixel a shape covers.
+const SUBSAMPLES: usize = 4;
+
+/// An image in memory, with its pixels stored row by row from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<Rgba>,
+}
+
+impl Image {
+    /// Creates an image filled with one color.
+    pub fn new(width: u32, height: u32, fill: Rgba) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![fill; width as usize * height as usize],
+        }
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[Rgba] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Rgba> {
+        if x < self.width && y < self.height {
+            Some(self.pixels[(y * self.width + x) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Draws a color over a pixel, letting what's under it show through as much as the color is transparent.
+    fn blend_pixel(&mut self, x: usize, y: usize, color: Rgba, coverage: f32) {
+        let pixel = &mut self.pixels[y * self.width as usize + x];
+        let alpha = color.a as f32 / 255. * coverage.min(1.);
+        let under = pixel.a as f32 / 255. * (1. - alpha);
+        let out = alpha + under;
+        if out <= 0. {
+            return;
+        }
+        let mix = |top: u8, bottom: u8| {
+            ((top as f32 * alpha + bottom as f32 * under) / out).round() as u8
+        };
+        *pixel = Rgba::new(
+            mix(color.r, pixel.r),
+            mix(color.g, pixel.g),
+            mix(color.b, pixel.b),
+            (out * 255.).round() as u8,
+        );
+    }
+
+    /// Fills the area covered by any of the given outlines, which are in pixels.
+    /// The edges of the area are antialiased, and outlines that overlap are filled only once.
+    fn fill_outlines(&mut self, outlines: &[Vec<GridPoint>], color: Rgba) {
+        let points = || outlines.iter().flatten();
+        let (Some(top), Some(bottom)) = (
+            points().map(|point| point.y).reduce(f32::min),
+            points().map(|point| point.y).reduce(f32::max),
+        ) else {
+            return;
+        };
+        let top = top.floor().max(0.) as usize;
+        let bottom = (bottom.ceil().max(0.) as usize).min(self.height as usize);
+
+        // Make every outline wind the same way, so overlaps add up instead of cancelling out
+        let outlines: Vec<Vec<GridPoint>> = outlines
+            .iter()
+            .map(|outline| {
+                let area: f32 = (0..outline.len())
+                    .map(|i| outline[i].cross(outline[(i + 1) % outline.len()]))
+                    .sum();
+                let mut outline = outline.clone();
+                if area < 0. {
+                    outline.reverse();
+                }
+                outline
+            })
+            .collect();
+
+        let width = self.width as usize;
+        let mut coverage = vec![0f32; width];
+        let mut crossings: Vec<(f32, i32)> = vec![];
+        for y in top..bottom {
+            coverage.iter_mut().for_each(|c| *c = 0.);
+            for subsample in 0..SUBSAMPLES {
+                let scan_y = y as f32 + (subsample as f32 + 0.5) / SUBSAMPLES as f32;
+                crossings.clear();
+                for outline in &outlines {
+                    for i in 0..outline.len() {
+                        let (from, to) = (outline[i], outline[(i + 1) % outline.len()]);
+                        if (from.y <= scan_y) != (to.y <= scan_y) {
+                            let x = from.x + (scan_y - from.y) * (to.x - from.x) / (to.y - from.y);
+                            crossings.push((x, if to.y > from.y { 1 } else { -1 }));
+                        }
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                // Everywhere the outlines wind around is inside
+                let mut winding = 0;
+                let mut span_start = 0.;
+                for &(x, direction) in &crossings {
+                    if winding == 0 {
+                        span_start = x;
+                    }
+                    winding += direction;
+                    if winding == 0 {
+                        let (from, to) = (span_start.max(0.), x.min(width as f32));
+                        let mut pixel = from.floor() as usize;
+                        while (pixel as f32) < to {
+                            let covered = to.min(pixel as f32 + 1.) - from.max(pixel as f32);
+                            coverage[pixel] += covered / SUBSAMPLES as f32;
+                            pixel += 1;
+                        }
+                    }
+                }
+            }
+            for (x, &covered) in coverage.iter().enumerate() {
+                if covered > 0. {
+                    self.blend_pixel(x, y, color, covered);
+                }
+            }
+        }
+    }
+
+    /// Encodes the image as a PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        let rgba: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| [pixel.r, pixel.g, pixel.b, pixel.a])
+            .collect();
+        encode_png(self.width, self.height, &rgba)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+        fs::write(path, self.to_png())
+    }
+
+    /// Decodes an image from a PNG file saved by `to_png`.
+    /// Returns `None` for other PNG files, which are usually compressed.
+    pub fn from_png(png: &[u8]) -> Option<Self> {
+        let (width, height, rgba) = decode_png(png)?;
+        Some(Image {
+            width,
+            height,
+            pixels: rgba
+                .chunks(4)
+                .map(|pixel| Rgba::new(pixel[0], pixel[1], pixel[2], pixel[3]))
+                .collect(),
+        })
+    }
+}
+
+/// Draws onto an image, scaling grid space to pixels.
+struct Rasterizer {
+    image: Image,
+    origin: GridPoint,
+    pixels_per_unit: f32,
+}
+
+impl Rasterizer {
+    fn to_pixels(&self, point: GridPoint) -> GridPoint {
+        (point - self.origin) * self.pixels_per_unit
+    }
+}
+
+impl Renderer for Rasterizer {
+    fn fill_polygon(&mut self, outline: &[GridPoint], color: Rgba) {
+        let outline = outline.iter().map(|&point| self.to_pixels(point)).collect();
+        self.image.fill_outlines(&[outline], color);
+    }
+
+    fn fill_circle(&mut self, center: GridPoint, radius: f32, color: Rgba) {
+        self.fill_polygon(&shapes::circle(center, radius), color);
+    }
+
+    fn draw_line(&mut self, line: &[GridPoint], width: f32, color: Rgba) {
+        // A rectangle along every segment, and a circle on every point for the round ends and corners
+        let mut outlines: Vec<Vec<GridPoint>> = line
+            .iter()
+            .map(|&point| shapes::circle(point, width / 2.))
+            .collect();
+        for segment in line.windows(2) {
+            let direction = segment[1] - segment[0];
+            if direction.mag() == 0. {
+                continue;
+            }
+            let side = direction.norm().perp() * (width / 2.);
+            outlines.push(vec![
+                segment[0] + side,
+                segment[1] + side,
+                segment[1] - side,
+                segment[0] - side,
+            ]);
+        }
+        let outlines: Vec<Vec<GridPoint>> = outlines
+            .into_iter()
+            .map(|outline| {
+                outline
+                    .into_iter()
+                    .map(|point| self.to_pixels(point))
+                    .collect()
+            })
+            .collect();
+        self.image.fill_outlines(&outlines, color);
+    }
+
+    /// Text isn't drawn, as there's no font to draw it with.
+    fn draw_text(&mut self, _position: GridPoint, _size: f32, _text: &str, _color: Rgba) {}
+}
+
+/// Renders a puzzle into an image, with `pixels_per_unit` pixels between neighbouring vertices.
+/// If a path is given, it's drawn on top as a solution, and any failing symbols are highlighted.
+pub fn render_image(
+    grid: &Grid,
+    path: Option<&Path>,
+    failing: &[FailingSymbol],
+    style: &Style,
+    pixels_per_unit: f32,
+) -> Image {
+    let (origin, bounds) = get_bounds(grid, style);
+    let size = (bounds * pixels_per_unit).round();
+    let mut rasterizer = Rasterizer {
+        image: Image::new(size.x as u32, size.y as u32, Rgba::new(0, 0, 0, 0)),
+        origin,
+        pixels_per_unit,
+    };
+    let overlay = Overlay {
+        lines: path
+            .map(|path| (get_path_polyline(&grid.snapshot(), path), style.line))
+            .into_iter()
+            .collect(),
+        failing,
+        ..Default::default()
+    };
+    draw_puzzle(&mut rasterizer, grid, &overlay, style);
+    rasterizer.image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gameplay::grid::{CellType, Color, TriangleCount, Vertex};
+
+    const RED: Rgba = Rgba::rgb(255, 0, 0);
+
+    #[test]
+    fn fills_with_antialiased_edges() {
+        let mut image = Image::new(4, 4, Rgba::rgb(0, 0, 0));
+        let square = vec![vec2!(1., 1.), vec2!(3., 1.), vec2!(3., 2.5), vec2!(1., 2.5)];
+        image.fill_outlines(&[square], RED);
+        assert_eq!(image.get_pixel(0, 0), Some(Rgba::rgb(0, 0, 0)));
+        assert_eq!(image.get_pixel(1, 1), Some(RED));
+        assert_eq!(image.get_pixel(2, 2), Some(Rgba::rgb(128, 0, 0)));
+        assert_eq!(image.get_pixel(3, 1), Some(Rgba::rgb(0, 0, 0)));
+        assert_eq!(image.get_pixel(4, 0), None);
+    }
+
+    #[test]
+    fn fills_overlaps_once() {
+        let mut image = Image::new(3, 1, Rgba::rgb(0, 0, 0));
+        let left = vec![vec2!(0., 0.), vec2!(2., 0.), vec2!(2., 1.), vec2!(0., 1.)];
+        // Wound the other way, and overlapping the middle pixel
+        let right = vec![vec2!(1., 0.), vec2!(1., 1.), vec2!(3., 1.), vec2!(3., 0.)];
+        image.fill_outlines(&[left, right], RED.with_alpha(128));
+        assert!(image
+            .get_pixels()
+            .iter()
+            .all(|&pixel| pixel == Rgba::rgb(128, 0, 0)));
+    }
+
+    /// How far each channel of a pixel may be from the golden image's.
+    const GOLDEN_TOLERANCE: u8 = 2;
+
+    /// Compares a rendered puzzle with the image saved in `golden/`.
+    /// Run with `UPDATE_GOLDEN=1` to save the current rendering instead, after checking it looks right.
+    #[test]
+    fn matches_golden_image() {
+        let mut grid = Grid::new(vec2!(2, 2));
+        grid.set_vertex_type(6, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_vertex_type(4, Vertex::Dot);
+        grid.set_cell_type(0, CellType::Square(Color::White));
+        grid.set_cell_type(1, CellType::Square(Color::Black));
+        grid.set_cell_type(3, CellType::Triangle(TriangleCount::Two));
+        let path = Path::from_vertices(vec![6, 3, 4, 5, 2]);
+        let image = render_image(&grid, Some(&path), &[], &Style::default(), 40.);
+        assert_eq!((image.get_width(), image.get_height()), (140, 140));
+
+        let golden = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("golden/solved_2x2.png");
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            image.save_png(&golden).unwrap();
+        }
+        let expected = Image::from_png(&fs::read(&golden).unwrap())
+            .unwrap_or_else(|| panic!("{golden:?} isn't an uncompressed RGBA PNG file"));
+        assert_eq!(
+            (expected.get_width(), expected.get_height()),
+            (image.get_width(), image.get_height())
+        );
+        // Antialiasing can round differently on other machines, so pixels may be a little off
+        let difference = image
+            .get_pixels()
+            .iter()
+            .zip(expected.get_pixels())
+            .flat_map(|(a, b)| {
+                [
+                    a.r.abs_diff(b.r),
+                    a.g.abs_diff(b.g),
+                    a.b.abs_diff(b.b),
+                    a.a.abs_diff(b.a),
+                ]
+            })
+            .max()
+            .unwrap_or(0);
+        assert!(
+            difference <= GOLDEN_TOLERANCE,
+            "rendering differs from {golden:?} by up to {difference} in a channel"
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! A renderer that remembers what it was asked to draw instead of drawing it, for tests.
+
+use gameplay::grid::GridPoint;
+
+use crate::{renderer::Renderer, style::Rgba};
+
+/// One call made to a [`Renderer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    Polygon {
+        outline: Vec<GridPoint>,
+        color: Rgba,
+    },
+    Circle {
+        center: GridPoint,
+        radius: f32,
+        color: Rgba,
+    },
+    Line {
+        line: Vec<GridPoint>,
+        width: f32,
+        color: Rgba,
+    },
+    Text {
+        position: GridPoint,
+        size: f32,
+        text: String,
+        color: Rgba,
+    },
+}
+
+/// Records every call made to it, in order.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingRenderer {
+    pub calls: Vec<DrawCall>,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets how many calls were made with the given color.
+    pub fn count_color(&self, color: Rgba) -> usize {
+        self.calls
+            .iter()
+            .filter(|call| match call {
+                DrawCall::Polygon { color: c, .. }
+                | DrawCall::Circle { color: c, .. }
+                | DrawCall::Line { color: c, .. }
+                | DrawCall::Text { color: c, .. } => *c == color,
+            })
+            .count()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn fill_polygon(&mut self, outline: &[GridPoint], color: Rgba) {
+        self.calls.push(DrawCall::Polygon {
+            outline: outline.to_vec(),
+            color,
+        });
+    }
+
+    fn fill_circle(&mut self, center: GridPoint, radius: f32, color: Rgba) {
+        self.calls.push(DrawCall::Circle {
+            center,
+            radius,
+            color,
+        });
+    }
+
+    fn draw_line(&mut self, line: &[GridPoint], width: f32, color: Rgba) {
+        self.calls.push(DrawCall::Line {
+            line: line.to_vec(),
+            width,
+            color,
+        });
+    }
+
+    fn draw_text(&mut self, position: GridPoint, size: f32, text: &str, color: Rgba) {
+        self.calls.push(DrawCall::Text {
+            position,
+            size,
+            text: text.to_string(),
+            color,
+        });
+    }
+}
@@ -0,0 +1,248 @@
+//! What a puzzle is drawn as, independent of what it's drawn onto.
+//! Frontends and exporters implement [`Renderer`] and leave the rest to [`draw_puzzle`].
+
+use std::collections::HashMap;
+
+use gameplay::{
+    grid::{CellType, EdgeID, EdgeType, Grid, GridPoint, Vertex},
+    session::{PuzzleSession, SessionState},
+    tracer::{get_exit_nub_direction, EXIT_NUB_LENGTH},
+    validation::FailingSymbol,
+};
+
+use crate::{
+    shapes,
+    style::{get_symbol_color, Rgba, Style},
+};
+
+/// Something puzzles can be drawn onto. Every position and size is in grid space.
+pub trait Renderer {
+    fn fill_polygon(&mut self, outline: &[GridPoint], color: Rgba);
+    fn fill_circle(&mut self, center: GridPoint, radius: f32, color: Rgba);
+    /// Draws a thick line through some points, with rounded ends and corners.
+    fn draw_line(&mut self, line: &[GridPoint], width: f32, color: Rgba);
+    /// Draws a line of text with its top left corner at `position`, `size` tall.
+    fn draw_text(&mut self, position: GridPoint, size: f32, text: &str, color: Rgba);
+}
+
+/// Everything drawn on a puzzle besides the puzzle itself.
+#[derive(Debug, Clone, Default)]
+pub struct Overlay<'a> {
+    /// Lines drawn along the grid with a start circle on their first point, from the bottom up.
+    pub lines: Vec<(Vec<GridPoint>, Rgba)>,
+    /// Symbols highlighted as failing.
+    pub failing: &'a [FailingSymbol],
+    /// Edges drawn in a different color than the rest of the grid.
+    pub edge_colors: HashMap<EdgeID, Rgba>,
+}
+
+impl<'a> Overlay<'a> {
+    /// Gets what's drawn on top of a session's puzzle: its line, the last line fading out behind it,
+    /// and its failing symbols while they blink.
+    pub fn for_session(session: &'a PuzzleSession, style: &Style) -> Self {
+        let mut overlay = Overlay::default();
+        if let Some((line, opacity)) = session.get_fading_line() {
+            overlay
+                .lines
+                .push((line.clone(), style.line.with_alpha((opacity * 255.) as u8)));
+        }
+        let color = match session.get_state() {
+            SessionState::Solved => style.solved_line,
+            SessionState::Failed => style.failed_line,
+            _ => style.line,
+        };
+        overlay.lines.push((session.get_line(), color));
+        if session.is_failure_blink_on() {
+            overlay.failing = session.get_failing_symbols();
+        }
+        overlay
+    }
+}
+
+/// Gets the top left corner and the size of the area a puzzle is drawn in, background included.
+pub fn get_bounds(grid: &Grid, style: &Style) -> (GridPoint, GridPoint) {
+    let border = style.panel_margin + style.background_margin;
+    let size = vec2cast!(grid.get_size(), f32);
+    (vec2!(-border, -border), size + border * 2.)
+}
+
+/// Draws a puzzle with an overlay on top of it.
+pub fn draw_puzzle(renderer: &mut impl Renderer, grid: &Grid, overlay: &Overlay, style: &Style) {
+    let snapshot = grid.snapshot();
+    let size = vec2cast!(grid.get_size(), f32);
+    let (_, bounds) = get_bounds(grid, style);
+
+    // Background and panel
+    renderer.fill_polygon(
+        &shapes::rounded_rectangle(size / 2., bounds, 0.),
+        style.background,
+    );
+    renderer.fill_polygon(
+        &shapes::rounded_rectangle(
+            size / 2.,
+            size + style.panel_margin * 2.,
+            shapes::PANEL_CORNER_RADIUS,
+        ),
+        style.panel,
+    );
+
+    let vertex_point = |vertex| vec2cast!(snapshot.get_vertex_position(vertex).unwrap(), f32);
+    let is_failing = |symbol: FailingSymbol| overlay.failing.contains(&symbol);
+
+    // Edges, leaving out the middle of gaps
+    for edge in snapshot.get_all_edge_ids_iter() {
+        let (a, b) = snapshot.get_edge(edge).unwrap().get_vertices_tuple();
+        if [a, b]
+            .iter()
+            .any(|&v| snapshot.get_vertex_type(v) == Some(Vertex::None))
+        {
+            continue;
+        }
+        let (a, b) = (vertex_point(a), vertex_point(b));
+        let color = overlay
+            .edge_colors
+            .get(&edge)
+            .copied()
+            .unwrap_or(style.grid_line);
+        match snapshot.get_edge_type(edge).unwrap() {
+            EdgeType::Gap => {
+                let part = (1. - style.gap_size) / 2.;
+                renderer.draw_line(&[a, a.lerp(b, part)], style.line_width, color);
+                renderer.draw_line(&[b, b.lerp(a, part)], style.line_width, color);
+            }
+            _ => renderer.draw_line(&[a, b], style.line_width, color),
+        }
+    }
+
+    // Starts and exits
+    for vertex in snapshot.get_all_vertex_ids_iter() {
+        let point = vertex_point(vertex);
+        match snapshot.get_vertex_type(vertex).unwrap() {
+            Vertex::Start => renderer.fill_circle(point, style.start_radius, style.grid_line),
+            Vertex::Exit => {
+                if let Some(direction) = get_exit_nub_direction(&snapshot, vertex) {
+                    renderer.draw_line(
+                        &[point, point + direction * EXIT_NUB_LENGTH],
+                        style.line_width,
+                        style.grid_line,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Lines go under the dots and symbols so they stay visible
+    for (line, color) in &overlay.lines {
+        if let Some(&start) = line.first() {
+            renderer.fill_circle(start, style.start_radius, *color);
+        }
+        renderer.draw_line(line, style.line_width, *color);
+    }
+
+    // Dots
+    for vertex in snapshot.get_all_vertex_ids_iter() {
+        if snapshot.get_vertex_type(vertex) == Some(Vertex::Dot) {
+            let color = if is_failing(FailingSymbol::VertexDot(vertex)) {
+                style.failing
+            } else {
+                style.dot
+            };
+            renderer.fill_polygon(
+                &shapes::hexagon(vertex_point(vertex), style.dot_radius),
+                color,
+            );
+        }
+    }
+    for edge in snapshot.get_all_edge_ids_iter() {
+        if snapshot.get_edge_type(edge) == Some(EdgeType::Dot) {
+            let (a, b) = snapshot.get_edge(edge).unwrap().get_vertices_tuple();
+            let color = if is_failing(FailingSymbol::EdgeDot(edge)) {
+                style.failing
+            } else {
+                style.dot
+            };
+            renderer.fill_polygon(
+                &shapes::hexagon(vertex_point(a).lerp(vertex_point(b), 0.5), style.dot_radius),
+                color,
+            );
+        }
+    }
+
+    // Symbols
+    for cell in snapshot.get_all_cell_ids_iter() {
+        let center = vec2cast!(snapshot.get_cell_position(cell).unwrap(), f32) + 0.5;
+        let highlight = |color: Rgba| {
+            if is_failing(FailingSymbol::Cell(cell)) {
+                style.failing
+            } else {
+                color
+            }
+        };
+        match snapshot.get_cell_type(cell).unwrap() {
+            CellType::Blank => {}
+            CellType::Square(color) => renderer.fill_polygon(
+                &shapes::rounded_square(center, shapes::SQUARE_SIZE, shapes::SQUARE_CORNER_RADIUS),
+                highlight(get_symbol_color(color)),
+            ),
+            CellType::Star(color) => renderer.fill_polygon(
+                &shapes::star(center, shapes::STAR_RADIUS),
+                highlight(get_symbol_color(color)),
+            ),
+            CellType::Triangle(count) => shapes::triangles(center, count)
+                .iter()
+                .for_each(|triangle| renderer.fill_polygon(triangle, highlight(style.triangle))),
+            CellType::Polyomino(polyomino) => shapes::polyomino(center, &polyomino)
+                .iter()
+                .for_each(|square| renderer.fill_polygon(square, highlight(style.polyomino))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::{DrawCall, RecordingRenderer};
+    use gameplay::grid::Color;
+
+    #[test]
+    fn draws_lines_under_symbols() {
+        let mut grid = Grid::new(vec2!(1, 1));
+        grid.set_vertex_type(2, Vertex::Start);
+        grid.set_vertex_type(1, Vertex::Exit);
+        grid.set_cell_type(0, CellType::Star(Color::Orange));
+        let style = Style::default();
+        let line = [vec2!(0., 1.), vec2!(0., 0.)];
+        let highlight = Rgba::rgb(1, 2, 3);
+        let overlay = Overlay {
+            lines: vec![(line.to_vec(), style.solved_line)],
+            failing: &[],
+            edge_colors: HashMap::from([(grid.get_edge_id_between(0, 1).unwrap(), highlight)]),
+        };
+        let mut renderer = RecordingRenderer::new();
+        draw_puzzle(&mut renderer, &grid, &overlay, &style);
+
+        assert_eq!(renderer.count_color(highlight), 1);
+        assert_eq!(renderer.count_color(style.solved_line), 2);
+        let line_index = renderer
+            .calls
+            .iter()
+            .position(
+                |call| matches!(call, DrawCall::Line { color, .. } if *color == style.solved_line),
+            )
+            .unwrap();
+        assert_eq!(
+            renderer.calls[line_index - 1],
+            DrawCall::Circle {
+                center: vec2!(0., 1.),
+                radius: style.start_radius,
+                color: style.solved_line
+            }
+        );
+        // The star comes last, on top of everything else
+        assert!(matches!(
+            renderer.calls.last(),
+            Some(DrawCall::Polygon { outline, .. }) if outline.len() == 16
+        ));
+    }
+}
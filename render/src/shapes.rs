@@ -0,0 +1,167 @@
+//! The outlines of the symbols drawn on puzzles, as polygons in grid space.
+
+use std::f32::consts::{PI, TAU};
+
+use gameplay::grid::{GridPoint, Polyomino, TriangleCount};
+
+/// The width of a square symbol.
+pub const SQUARE_SIZE: f32 = 0.26;
+/// How rounded the corners of a square symbol are.
+pub const SQUARE_CORNER_RADIUS: f32 = 0.07;
+/// The distance from the center of a star to its points.
+pub const STAR_RADIUS: f32 = 0.17;
+/// The width of a single triangle.
+pub const TRIANGLE_SIZE: f32 = 0.13;
+/// The distance between the centers of triangles in the same cell.
+pub const TRIANGLE_SPACING: f32 = 0.17;
+/// The width of a single square of a polyomino.
+pub const POLYOMINO_SQUARE_SIZE: f32 = 0.09;
+/// The space between the squares of a polyomino.
+pub const POLYOMINO_SQUARE_GAP: f32 = 0.03;
+/// How far polyominoes that may be rotated are tilted, in radians.
+pub const POLYOMINO_ROTATABLE_TILT: f32 = PI / 12.;
+/// How rounded the corners of the panel are.
+pub const PANEL_CORNER_RADIUS: f32 = 0.15;
+/// How many points are used for every quarter circle of a rounded shape.
+const CORNER_SEGMENTS: usize = 4;
+
+/// Gets the points of a regular polygon, with its first point at `angle` radians clockwise from the right.
+pub fn regular_polygon(center: GridPoint, radius: f32, sides: usize, angle: f32) -> Vec<GridPoint> {
+    (0..sides)
+        .map(|i| {
+            let angle = angle + TAU * i as f32 / sides as f32;
+            center + vec2!(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+/// Gets the hexagon a dot is drawn as, with two of its corners pointing left and right.
+pub fn hexagon(center: GridPoint, radius: f32) -> Vec<GridPoint> {
+    regular_polygon(center, radius, 6, 0.)
+}
+
+/// Gets a circle, made out of enough points to look round at the sizes puzzles are drawn at.
+pub fn circle(center: GridPoint, radius: f32) -> Vec<GridPoint> {
+    regular_polygon(center, radius, CORNER_SEGMENTS * 8, 0.)
+}
+
+/// Gets a rectangle with rounded corners. Corners with a radius of 0 are left sharp.
+pub fn rounded_rectangle(center: GridPoint, size: GridPoint, corner_radius: f32) -> Vec<GridPoint> {
+    let inner = size / 2. - corner_radius;
+    let segments = if corner_radius > 0. {
+        CORNER_SEGMENTS
+    } else {
+        0
+    };
+    [
+        (vec2!(inner.x, inner.y), 0.),
+        (vec2!(-inner.x, inner.y), PI / 2.),
+        (vec2!(-inner.x, -inner.y), PI),
+        (vec2!(inner.x, -inner.y), PI * 1.5),
+    ]
+    .into_iter()
+    .flat_map(|(corner, start)| {
+        (0..=segments).map(move |i| {
+            let angle = start + PI / 2. * i as f32 / CORNER_SEGMENTS as f32;
+            center + corner + vec2!(angle.cos(), angle.sin()) * corner_radius
+        })
+    })
+    .collect()
+}
+
+/// Gets a square with rounded corners.
+pub fn rounded_square(center: GridPoint, size: f32, corner_radius: f32) -> Vec<GridPoint> {
+    rounded_rectangle(center, vec2!(size, size), corner_radius)
+}
+
+/// Gets a star: two squares on top of each other, one turned by 45 degrees.
+pub fn star(center: GridPoint, radius: f32) -> Vec<GridPoint> {
+    // The inner corners are where the sides of the two squares cross
+    let inner_radius = radius * (PI / 4.).cos() / (PI / 8.).cos();
+    (0..16)
+        .map(|i| {
+            let angle = PI / 8. * i as f32;
+            let distance = if i % 2 == 0 { radius } else { inner_radius };
+            center + vec2!(angle.cos(), angle.sin()) * distance
+        })
+        .collect()
+}
+
+/// Gets the triangles drawn in a cell, side by side, each pointing up.
+pub fn triangles(center: GridPoint, count: TriangleCount) -> Vec<Vec<GridPoint>> {
+    let count = count as usize;
+    let height = TRIANGLE_SIZE * 3f32.sqrt() / 2.;
+    (0..count)
+        .map(|i| {
+            let x = center.x + (i as f32 - (count - 1) as f32 / 2.) * TRIANGLE_SPACING;
+            vec![
+                vec2!(x, center.y - height / 2.),
+                vec2!(x + TRIANGLE_SIZE / 2., center.y + height / 2.),
+                vec2!(x - TRIANGLE_SIZE / 2., center.y + height / 2.),
+            ]
+        })
+        .collect()
+}
+
+/// Gets the squares a polyomino is drawn as, centered on a point.
+/// Polyominoes that may be rotated are drawn tilted, like in the game.
+pub fn polyomino(center: GridPoint, polyomino: &Polyomino) -> Vec<Vec<GridPoint>> {
+    let size = polyomino.get_size();
+    let step = POLYOMINO_SQUARE_SIZE + POLYOMINO_SQUARE_GAP;
+    let offset = vec2!(size.x as f32 - 1., size.y as f32 - 1.) * (step / 2.);
+    let tilt = if polyomino.is_rotatable() {
+        POLYOMINO_ROTATABLE_TILT
+    } else {
+        0.
+    };
+    let rotate = |point: GridPoint| {
+        vec2!(
+            point.x * tilt.cos() - point.y * tilt.sin(),
+            point.x * tilt.sin() + point.y * tilt.cos()
+        )
+    };
+    polyomino
+        .get_squares()
+        .into_iter()
+        .map(|square| {
+            let square_center = vec2cast!(square, f32) * step - offset;
+            let half = POLYOMINO_SQUARE_SIZE / 2.;
+            [
+                vec2!(-half, -half),
+                vec2!(half, -half),
+                vec2!(half, half),
+                vec2!(-half, half),
+            ]
+            .into_iter()
+            .map(|corner| center + rotate(square_center + corner))
+            .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_are_centered() {
+        let center: GridPoint = vec2!(1.5, 0.5);
+        let average = |points: &[GridPoint]| {
+            points
+                .iter()
+                .fold(vec2!(0., 0.), |sum: GridPoint, &point| sum + point)
+                / points.len() as f32
+        };
+        for shape in [
+            hexagon(center, 0.1),
+            star(center, STAR_RADIUS),
+            rounded_square(center, SQUARE_SIZE, SQUARE_CORNER_RADIUS),
+        ] {
+            assert!((average(&shape) - center).mag() < 1e-4);
+        }
+        let domino = Polyomino::from_mask(0b11, true).unwrap();
+        let squares: Vec<GridPoint> = polyomino(center, &domino).concat();
+        assert!((average(&squares) - center).mag() < 1e-4);
+        assert_eq!(triangles(center, TriangleCount::Three).len(), 3);
+    }
+}
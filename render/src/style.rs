@@ -0,0 +1,106 @@
+use gameplay::grid::Color;
+
+/// A color with an alpha channel, 0 to 255 per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    /// Creates a fully opaque color.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Rgba { r, g, b, a: 255 }
+    }
+
+    /// Gets this color with a different opacity.
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Rgba { a, ..self }
+    }
+
+    /// Formats the color channels as `#rrggbb`, leaving out the alpha channel.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Gets the color a symbol of the given color is drawn in.
+pub fn get_symbol_color(color: Color) -> Rgba {
+    match color {
+        Color::Black => Rgba::rgb(0x10, 0x10, 0x10),
+        Color::White => Rgba::rgb(0xf8, 0xf8, 0xf8),
+        Color::Cyan => Rgba::rgb(0x00, 0xe0, 0xe0),
+        Color::Magenta => Rgba::rgb(0xe0, 0x00, 0xe0),
+        Color::Yellow => Rgba::rgb(0xff, 0xe0, 0x00),
+        Color::Red => Rgba::rgb(0xe0, 0x20, 0x20),
+        Color::Green => Rgba::rgb(0x20, 0xc0, 0x20),
+        Color::Blue => Rgba::rgb(0x20, 0x40, 0xe0),
+        Color::Orange => Rgba::rgb(0xff, 0x8c, 0x00),
+    }
+}
+
+/// How a puzzle looks: its colors, and the sizes of everything on it in grid units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The color around the panel.
+    pub background: Rgba,
+    /// The color of the panel the grid is drawn on.
+    pub panel: Rgba,
+    /// The color of the grid's edges, starts and exits.
+    pub grid_line: Rgba,
+    /// The color of the line the player draws.
+    pub line: Rgba,
+    /// The color of the line after it solved the puzzle.
+    pub solved_line: Rgba,
+    /// The color of the line after it failed to solve the puzzle.
+    pub failed_line: Rgba,
+    /// The color of dots on vertices and edges.
+    pub dot: Rgba,
+    /// The color symbols that aren't satisfied by the line are highlighted with.
+    pub failing: Rgba,
+    /// The color of triangles.
+    pub triangle: Rgba,
+    /// The color of polyominoes.
+    pub polyomino: Rgba,
+    /// How far the panel reaches past the outermost vertices.
+    pub panel_margin: f32,
+    /// How far the background reaches past the panel.
+    pub background_margin: f32,
+    /// How thick the grid's edges and the player's line are.
+    pub line_width: f32,
+    /// The radius of the circle on a start.
+    pub start_radius: f32,
+    /// The radius of the hexagon dots.
+    pub dot_radius: f32,
+    /// How much of an edge a gap takes out of its middle, as a fraction of the edge.
+    pub gap_size: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            background: Rgba::rgb(0x10, 0x10, 0x18),
+            panel: Rgba::rgb(0x30, 0x40, 0x7a),
+            grid_line: Rgba::rgb(0x1c, 0x24, 0x50),
+            line: Rgba::rgb(0xf0, 0xf0, 0xff),
+            solved_line: Rgba::rgb(0xff, 0xe0, 0x40),
+            failed_line: Rgba::rgb(0x60, 0x60, 0x70),
+            dot: Rgba::rgb(0x10, 0x10, 0x10),
+            failing: Rgba::rgb(0xff, 0x30, 0x30),
+            triangle: Rgba::rgb(0xff, 0x8c, 0x00),
+            polyomino: Rgba::rgb(0xff, 0xe0, 0x00),
+            panel_margin: 0.5,
+            background_margin: 0.25,
+            line_width: 0.2,
+            start_radius: 0.22,
+            dot_radius: 0.08,
+            gap_size: 0.3,
+        }
+    }
+}
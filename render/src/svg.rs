@@ -0,0 +1,188 @@
+//! Rendering puzzles into SVG documents, so they can be shown without running a frontend.
+
+use std::fmt::Write;
+
+use gameplay::{
+    grid::{Grid, GridPoint},
+    path::Path,
+    tracer::get_path_polyline,
+    validation::FailingSymbol,
+};
+
+use crate::{
+    renderer::{draw_puzzle, get_bounds, Overlay, Renderer},
+    style::{Rgba, Style},
+};
+
+/// How many pixels wide one grid unit is in the size the document asks to be shown at.
+pub const SVG_PIXELS_PER_UNIT: f32 = 100.;
+
+/// Formats a number with at most three decimals, which is plenty at the sizes puzzles are drawn at.
+fn number(value: f32) -> String {
+    let formatted = format!("{value:.3}");
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+fn points(points: &[GridPoint]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{},{}", number(point.x), number(point.y)))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Escapes the characters that would be read as markup.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Gets the `fill` or `stroke` attributes for a color, including its opacity if it isn't opaque.
+fn paint(attribute: &str, color: Rgba) -> String {
+    if color.a == 255 {
+        format!("{attribute}=\"{}\"", color.to_hex())
+    } else {
+        format!(
+            "{attribute}=\"{}\" {attribute}-opacity=\"{}\"",
+            color.to_hex(),
+            number(color.a as f32 / 255.)
+        )
+    }
+}
+
+/// Builds up the elements of an SVG document.
+struct SvgWriter {
+    document: String,
+}
+
+impl Renderer for SvgWriter {
+    fn fill_polygon(&mut self, outline: &[GridPoint], color: Rgba) {
+        writeln!(
+            self.document,
+            "<polygon points=\"{}\" {}/>",
+            points(outline),
+            paint("fill", color)
+        )
+        .unwrap();
+    }
+
+    fn fill_circle(&mut self, center: GridPoint, radius: f32, color: Rgba) {
+        writeln!(
+            self.document,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>",
+            number(center.x),
+            number(center.y),
+            number(radius),
+            paint("fill", color)
+        )
+        .unwrap();
+    }
+
+    fn draw_line(&mut self, line: &[GridPoint], width: f32, color: Rgba) {
+        writeln!(
+            self.document,
+            "<polyline points=\"{}\" fill=\"none\" {} stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+            points(line),
+            paint("stroke", color),
+            number(width)
+        )
+        .unwrap();
+    }
+
+    fn draw_text(&mut self, position: GridPoint, size: f32, text: &str, color: Rgba) {
+        writeln!(
+            self.document,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" font-family=\"sans-serif\" dominant-baseline=\"hanging\" {}>{}</text>",
+            number(position.x),
+            number(position.y),
+            number(size),
+            paint("fill", color),
+            escape(text)
+        )
+        .unwrap();
+    }
+}
+
+/// Renders a puzzle into an SVG document.
+/// If a path is given, it's drawn on top as a solution, and any failing symbols are highlighted.
+pub fn render_svg(
+    grid: &Grid,
+    path: Option<&Path>,
+    failing: &[FailingSymbol],
+    style: &Style,
+) -> String {
+    let (origin, bounds) = get_bounds(grid, style);
+    let mut svg = SvgWriter {
+        document: String::new(),
+    };
+    writeln!(
+        svg.document,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">",
+        number(origin.x),
+        number(origin.y),
+        number(bounds.x),
+        number(bounds.y),
+        number(bounds.x * SVG_PIXELS_PER_UNIT),
+        number(bounds.y * SVG_PIXELS_PER_UNIT),
+    )
+    .unwrap();
+    let overlay = Overlay {
+        lines: path
+            .map(|path| (get_path_polyline(&grid.snapshot(), path), style.line))
+            .into_iter()
+            .collect(),
+        failing,
+        ..Default::default()
+    };
+    draw_puzzle(&mut svg, grid, &overlay, style);
+    svg.document.push_str("</svg>\n");
+    svg.document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::get_symbol_color;
+    use gameplay::grid::{CellType, Color, EdgeType, Polyomino, TriangleCount, Vertex};
+
+    #[test]
+    fn renders_every_part() {
+        let mut grid = Grid::new(vec2!(2, 1));
+        grid.set_vertex_type(3, Vertex::Start);
+        grid.set_vertex_type(2, Vertex::Exit);
+        grid.set_vertex_type(1, Vertex::Dot);
+        grid.set_edge_type(grid.get_edge_id_between(0, 1).unwrap(), EdgeType::Gap);
+        grid.set_cell_type(0, CellType::Square(Color::White));
+        grid.set_cell_type(1, CellType::Triangle(TriangleCount::Two));
+        let svg = render_svg(&grid, None, &[], &Style::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-0.75 -0.75 3.5 2.5\" width=\"350\" height=\"250\">\n"));
+        assert!(svg.ends_with("</svg>\n"));
+        // 7 edges, one of them split by a gap, and the exit's nub
+        assert_eq!(svg.matches("<polyline").count(), 9);
+        assert_eq!(svg.matches("<circle").count(), 1);
+        // The background, the panel, the dot, the square and the two triangles
+        assert_eq!(svg.matches("<polygon").count(), 6);
+        assert!(svg.contains(&get_symbol_color(Color::White).to_hex()));
+    }
+
+    #[test]
+    fn renders_solutions_and_failures() {
+        let mut grid = Grid::new(vec2!(1, 1));
+        grid.set_vertex_type(2, Vertex::Start);
+        grid.set_vertex_type(1, Vertex::Exit);
+        let l = Polyomino::from_mask(0b11, false).unwrap();
+        grid.set_cell_type(0, CellType::Polyomino(l));
+        let style = Style::default();
+        let path = Path::from_vertices(vec![2, 0, 1]);
+        let svg = render_svg(&grid, Some(&path), &[FailingSymbol::Cell(0)], &style);
+        // The solution runs into the exit's nub
+        assert!(svg.contains("points=\"0,1 0,0 1,0 1.25,0\""));
+        assert_eq!(svg.matches(&style.failing.to_hex()).count(), 2);
+        assert!(!svg.contains(&style.polyomino.to_hex()));
+    }
+}
@@ -12,7 +12,10 @@
 #![allow(dead_code)]
 use std::ops::*;
 
+use crate::vec2;
+
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2<T> {
     pub x: T,
     pub y: T,
@@ -38,40 +41,43 @@ impl<T> Vector2<T> {
     }
 }
 
-impl<T> Into<(T, T)> for Vector2<T> {
-    fn into(self) -> (T, T) {
-        (self.x, self.y)
+impl<T> From<Vector2<T>> for (T, T) {
+    fn from(vector: Vector2<T>) -> (T, T) {
+        (vector.x, vector.y)
     }
 }
 
-impl<T> Into<[T; 2]> for Vector2<T> {
-    fn into(self) -> [T; 2] {
-        [self.x, self.y]
+impl<T> From<Vector2<T>> for [T; 2] {
+    fn from(vector: Vector2<T>) -> [T; 2] {
+        [vector.x, vector.y]
     }
 }
 
+#[macro_export]
 macro_rules! vec2 {
     ($x: expr, $y: expr) => {
-        Vector2::new($x, $y)
+        $crate::Vector2::new($x, $y)
     };
     ($x: expr) => {
-        Vector2::new($x, $x)
+        $crate::Vector2::new($x, $x)
     };
     () => {
-        Vector2::default()
+        $crate::Vector2::default()
     };
 }
 
-pub(crate) use vec2;
-
+/// Casts both coordinates of a vector with `as`. Works without `vec2!` imported:
+/// ```
+/// use witness_core::{vec2cast, Vector2};
+/// assert_eq!(vec2cast!(Vector2::new(3u8, 4u8), i32), Vector2::new(3, 4));
+/// ```
+#[macro_export]
 macro_rules! vec2cast {
     ($vec: expr, $type: ty) => {
-        vec2!($vec.x as $type, $vec.y as $type)
+        $crate::vec2!($vec.x as $type, $vec.y as $type)
     };
 }
 
-pub(crate) use vec2cast;
-
 // Equivalence traits
 
 impl<T: PartialEq> PartialEq for Vector2<T> {
@@ -432,6 +438,7 @@ where
     Vector2<T>:
         Sub<<<<<T as Mul>::Output as Add>::Output as Mul<Vector2<T>>>::Output as Mul<T>>::Output>,
 {
+    #[allow(clippy::type_complexity)]
     pub fn reflect(
         self,
         along: Self,